@@ -8,8 +8,8 @@ if ! curl -s --max-time 3 --head https://google.com > /dev/null; then
 fi
 
 # Flag to disable info output
-GITHUB_USER=BleynChannel
-GITHUB_REPO=Kite-Dots
+GITHUB_USER=${GITHUB_USER:-BleynChannel}
+GITHUB_REPO=${GITHUB_REPO:-Kite-Dots}
 
 # Function to show help
 show_help() {
@@ -75,6 +75,29 @@ check_github_commit() {
     fi
 }
 
+# Returns success if release $1 is newer than $2 (leading "v" is ignored)
+version_newer() {
+    LATEST=${1#[vV]}
+    CURRENT=${2#[vV]}
+
+    if [ -z "$CURRENT" ]; then
+        return 0
+    fi
+    if [ "$LATEST" = "$CURRENT" ]; then
+        return 1
+    fi
+
+    # A release is newer than its own prerelease (1.2.0 > 1.2.0-rc1)
+    if [ "${CURRENT%%-*}" = "$LATEST" ]; then
+        return 0
+    fi
+    if [ "${LATEST%%-*}" = "$CURRENT" ]; then
+        return 1
+    fi
+
+    [ "$(printf '%s\n%s\n' "$CURRENT" "$LATEST" | sort -V | tail -n 1)" = "$LATEST" ]
+}
+
 # Function to check updates for Stable
 check_stable_updates() {
     info "Checking updates for Stable..."
@@ -107,8 +130,8 @@ check_stable_updates() {
         return 1
     fi
 
-    # Compare versions
-    if [ "$LATEST_RELEASE" != "$CURRENT_VERSION" ]; then
+    # Compare versions: only a newer release counts as an update
+    if version_newer "$LATEST_RELEASE" "$CURRENT_VERSION"; then
         if ! $NO_INFO; then
             info "Update available! Latest version: $LATEST_RELEASE"
         else
@@ -1,7 +1,7 @@
 #!/bin/bash
 
-GITHUB_USER=BleynChannel
-GITHUB_REPO=Kite-Dots
+GITHUB_USER=${GITHUB_USER:-BleynChannel}
+GITHUB_REPO=${GITHUB_REPO:-Kite-Dots}
 
 # Function to show help
 show_help() {
@@ -95,12 +95,13 @@ if [ "$NO_CONFIRM" = false ]; then
   fi
 fi
 
+SOURCE_DIR=$(dirname "$(realpath "$0")")
 TEMP_DIR=$(mktemp -d)
 chown -R "$SUDO_USER":"$SUDO_USER" "$TEMP_DIR"
 
 # Step 3: Update packages
 if [ -f /var/lib/pacman/db.lck ]; then
-  echo -e "Error: Pacman database is locked. Another pacman process may be running.\nTry running: sudo rm /var/lib/pacman/db.lck" >&2
+  echo -e "Error: Pacman database is locked. Another pacman, yay or paru process may be running.\nWait for it to finish, or run kite-tools to check whether the lock is stale and remove it." >&2
   exit 1
 fi
 
@@ -184,7 +185,7 @@ if ! cp "$PKG_DIR/os-release" /etc/; then
     echo "Error: Failed to copy os-release" >&2
     exit 1
 fi
-if ! cp "$PKG_DIR/uninstall.sh" /usr/src/kite-tools/; then
+if ! cp "$PKG_DIR/uninstall.sh" "$SOURCE_DIR/"; then
     echo "Error: Failed to copy uninstall.sh" >&2
     exit 1
 fi
@@ -0,0 +1,143 @@
+#!/bin/bash
+
+# Function to show help
+show_help() {
+  cat <<EOF
+Usage: $0 --snapshot <dir> [options]
+
+Returns the system to a snapshot taken by kite-tools before an update.
+The snapshot directory contains os-release, os-release.backup and packages.txt.
+
+Options:
+  -h, --help             Show this help
+  --snapshot <dir>       Snapshot directory
+  --reinstall            Reinstall the version from the snapshot over the current system
+  --packages             Install packages from the snapshot that are missing now
+  --configs <archive>    Extract a config archive (tar.zst) over the current files
+  --no-info              Disable info messages
+
+Examples:
+  $0 --snapshot ~/.local/state/kite-tools/snapshots/20261017-120000 --reinstall --packages
+EOF
+  exit 0
+}
+
+# Обработка аргументов
+SNAPSHOT=""
+CONFIGS=""
+REINSTALL=false
+PACKAGES=false
+NO_INFO=false
+
+while [[ $# -gt 0 ]]; do
+  case $1 in
+    -h|--help)
+      show_help
+      ;;
+    --snapshot)
+      if [[ -n $2 ]]; then
+        SNAPSHOT=$2
+        shift
+      else
+        echo "Error: Directory not specified after --snapshot flag" >&2
+        exit 1
+      fi
+      ;;
+    --configs)
+      if [[ -n $2 ]]; then
+        CONFIGS=$2
+        shift
+      else
+        echo "Error: Archive not specified after --configs flag" >&2
+        exit 1
+      fi
+      ;;
+    --reinstall)
+      REINSTALL=true
+      ;;
+    --packages)
+      PACKAGES=true
+      ;;
+    --no-info)
+      NO_INFO=true
+      ;;
+    *)
+      echo "Error: Unknown argument '$1'" >&2
+      show_help
+      exit 1
+      ;;
+  esac
+  shift
+done
+
+# Function to output information
+info() {
+  if [ "$NO_INFO" = false ]; then
+    echo "[INFO] $1"
+  fi
+}
+
+SOURCE_DIR=$(dirname "$(realpath "$0")")
+
+# Step 1: Check snapshot
+info "Checking snapshot..."
+if [ -z "$SNAPSHOT" ] || [ ! -f "$SNAPSHOT/os-release" ] || [ ! -f "$SNAPSHOT/packages.txt" ]; then
+  echo "Error: Snapshot not found: '$SNAPSHOT'" >&2
+  exit 1
+fi
+if [ -n "$CONFIGS" ] && [ ! -f "$CONFIGS" ]; then
+  echo "Error: Config archive not found: '$CONFIGS'" >&2
+  exit 1
+fi
+
+TYPE=$(grep '^BUILD_ID=' "$SNAPSHOT/os-release" | cut -d= -f2 | tr -d '"')
+VERSION=$(grep '^VERSION_ID=' "$SNAPSHOT/os-release" | cut -d= -f2 | tr -d '"')
+
+# Step 2: Reinstall the previous version
+if [ "$REINSTALL" = true ]; then
+  # update.sh works only on a Kite system
+  if ! cp -f "$SNAPSHOT/os-release" /etc/os-release; then
+    echo "Error: Failed to restore os-release" >&2
+    exit 1
+  fi
+
+  info "Reinstalling previous version..."
+  if ! bash "$SOURCE_DIR/update.sh" -t "$TYPE" -v "$VERSION" --no-confirm --no-reboot --no-uninstall --no-info; then
+    echo "Error: Failed to reinstall version $VERSION" >&2
+    exit 1
+  fi
+fi
+
+# Step 3: Install packages that were removed
+if [ "$PACKAGES" = true ]; then
+  info "Restoring packages..."
+  MISSING=$(comm -23 <(sort -u "$SNAPSHOT/packages.txt") <(pacman -Qq | sort -u))
+  if [ -n "$MISSING" ]; then
+    if ! pacman -S --needed --noconfirm $MISSING; then
+      echo "Error: Failed to install packages:" $MISSING >&2
+      exit 1
+    fi
+  fi
+fi
+
+# Step 4: Restore configuration files
+if [ -n "$CONFIGS" ]; then
+  info "Restoring configuration files..."
+  if ! tar --zstd -xpf "$CONFIGS" -C /; then
+    echo "Error: Failed to extract '$CONFIGS'" >&2
+    exit 1
+  fi
+fi
+
+# Step 5: Restore os-release
+info "Restoring os-release..."
+if ! cp -f "$SNAPSHOT/os-release" /etc/os-release; then
+  echo "Error: Failed to restore os-release" >&2
+  exit 1
+fi
+if [ -f "$SNAPSHOT/os-release.backup" ] && ! cp -f "$SNAPSHOT/os-release.backup" /etc/os-release.backup; then
+  echo "Error: Failed to restore os-release backup" >&2
+  exit 1
+fi
+
+info "Rollback completed successfully!"
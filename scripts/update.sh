@@ -1,7 +1,7 @@
 #!/bin/bash 
 
-GITHUB_USER=BleynChannel
-GITHUB_REPO=Kite-Dots
+GITHUB_USER=${GITHUB_USER:-BleynChannel}
+GITHUB_REPO=${GITHUB_REPO:-Kite-Dots}
 
 # Function to show help
 show_help() {
@@ -11,22 +11,28 @@ Usage: $0 [options]
 Options:
   -h, --help                          Show this help
   -v <version> | --version <version>  Skip check and specify system version
+  -t <type> | --type <type>           Switch to another system type (stable, developer, experimental)
   --no-confirm                        Skip installation confirmation
   --no-info                           Disable info messages
   --no-reboot                         Skip system reboot
+  --no-uninstall                      Install over the current system without removing it
+                                      (used by kite-tools to reinstall a version after a failed update)
 
 Examples:
   $0
   $0 -v 0.0.0 --no-confirm
+  $0 -t stable -v 1.0.0 --no-confirm
 EOF
   exit 0
 }
 
 # Обработка аргументов
 VERSION=""
+NEW_TYPE=""
 NO_CONFIRM=false
 NO_INFO=false
 NO_REBOOT=false
+NO_UNINSTALL=false
 
 while [[ $# -gt 0 ]]; do
   case $1 in
@@ -42,6 +48,15 @@ while [[ $# -gt 0 ]]; do
         exit 1
       fi
       ;;
+    -t|--type)
+      if [[ -n $2 ]]; then
+        NEW_TYPE=$2
+        shift
+      else
+        echo "Error: System type not specified after -t|--type flag" >&2
+        exit 1
+      fi
+      ;;
     --no-confirm)
       NO_CONFIRM=true
       ;;
@@ -51,6 +66,9 @@ while [[ $# -gt 0 ]]; do
     --no-reboot)
       NO_REBOOT=true
       ;;
+    --no-uninstall)
+      NO_UNINSTALL=true
+      ;;
     *)
       echo "Error: Unknown argument '$1'" >&2
       show_help
@@ -78,6 +96,20 @@ fi
 SOURCE_DIR=$(dirname "$(realpath "$0")")
 TYPE=$(grep '^BUILD_ID=' /etc/os-release | cut -d= -f2 | tr -d '"')
 
+# Switching the system type: the new version is taken from the new type
+if [ -n "$NEW_TYPE" ]; then
+  case $NEW_TYPE in
+    stable|developer|experimental)
+      info "Switching system type: $TYPE -> $NEW_TYPE"
+      TYPE=$NEW_TYPE
+      ;;
+    *)
+      echo "Error: Unknown system type '$NEW_TYPE'" >&2
+      exit 1
+      ;;
+  esac
+fi
+
 # Step 2: Check for updates
 if [ -z "$VERSION" ]; then
   info "Checking for updates..."
@@ -120,8 +152,8 @@ fi
 
 # Step 4: Update packages
 if [ -f /var/lib/pacman/db.lck ]; then
-  echo "Error: Pacman database is locked. Another pacman process may be running."
-  echo "Try running: rm /var/lib/pacman/db.lck" >&2
+  echo "Error: Pacman database is locked. Another pacman, yay or paru process may be running." >&2
+  echo "Wait for it to finish, or run kite-tools to check whether the lock is stale and remove it." >&2
   exit 1
 fi
 
@@ -147,14 +179,22 @@ case $TYPE in
         echo "Error: Failed to download installation package" >&2
         exit 1
     fi
-    (cd "$TEMP_DIR/kite" && git checkout $VERSION)
+    # The clone holds only the branch HEAD, so an older commit is fetched separately
+    if ! (cd "$TEMP_DIR/kite" && sudo -u $SUDO_USER git fetch --depth 1 origin $VERSION && sudo -u $SUDO_USER git checkout $VERSION); then
+        echo "Error: Failed to check out commit $VERSION" >&2
+        exit 1
+    fi
     ;;
   experimental)
     if ! sudo -u $SUDO_USER git clone --depth 1 --branch experimental https://github.com/$GITHUB_USER/$GITHUB_REPO.git "$TEMP_DIR/kite"; then
         echo "Error: Failed to download installation package" >&2
         exit 1
     fi
-    (cd "$TEMP_DIR/kite" && git checkout $VERSION)
+    # The clone holds only the branch HEAD, so an older commit is fetched separately
+    if ! (cd "$TEMP_DIR/kite" && sudo -u $SUDO_USER git fetch --depth 1 origin $VERSION && sudo -u $SUDO_USER git checkout $VERSION); then
+        echo "Error: Failed to check out commit $VERSION" >&2
+        exit 1
+    fi
     ;;
 esac
 PKG_DIR="$TEMP_DIR/kite"
@@ -167,11 +207,14 @@ if ! (cd "$PKG_DIR" && git lfs install && git lfs pull); then
 fi
 
 # Step 6: Change version
+# With --no-uninstall the package is installed over the current system
 if [ "$NO_INFO" = true ]; then
-    info "Removing old version..."
-    if ! bash "$SOURCE_DIR/uninstall.sh" full --no-confirm --no-reboot --no-info; then
-        echo "Error: Uninstall script failed" >&2
-        exit 1
+    if [ "$NO_UNINSTALL" = false ]; then
+        info "Removing old version..."
+        if ! bash "$SOURCE_DIR/uninstall.sh" full --no-confirm --no-reboot --no-info; then
+            echo "Error: Uninstall script failed" >&2
+            exit 1
+        fi
     fi
 
     info "Running installation script..."
@@ -180,10 +223,12 @@ if [ "$NO_INFO" = true ]; then
         exit 1
     fi
 else
-    info "Removing old version..."
-    if ! bash "$SOURCE_DIR/uninstall.sh" full --no-confirm --no-reboot; then
-        echo "Error: Uninstall script failed" >&2
-        exit 1
+    if [ "$NO_UNINSTALL" = false ]; then
+        info "Removing old version..."
+        if ! bash "$SOURCE_DIR/uninstall.sh" full --no-confirm --no-reboot; then
+            echo "Error: Uninstall script failed" >&2
+            exit 1
+        fi
     fi
 
     info "Running installation script..."
@@ -206,14 +251,17 @@ if ! cp -f "$PKG_DIR/os-release" /etc/; then
     echo "Error: Failed to copy os-release" >&2
     exit 1
 fi
-if ! cp -f "$PKG_DIR/uninstall.sh" /usr/src/kite-tools/; then
+if ! cp -f "$PKG_DIR/uninstall.sh" "$SOURCE_DIR/"; then
     echo "Error: Failed to copy uninstall.sh" >&2
     exit 1
 fi
 
 # Step 9: Change BUILD_ID and VERSION_ID in os-release
 info "Applying new changes to system..."
-# sed -i "s/BUILD_ID=.*$/BUILD_ID=$TYPE/" /etc/os-release
+if ! sed -i "s/BUILD_ID=.*$/BUILD_ID=$TYPE/" /etc/os-release; then
+    echo "Error: Failed to update os-release" >&2
+    exit 1
+fi
 if ! sed -i "s/VERSION_ID=.*$/VERSION_ID=$VERSION/" /etc/os-release; then
     echo "Error: Failed to update os-release" >&2
     exit 1
@@ -0,0 +1,339 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+const TAB_WIDTH: usize = 8;
+
+enum ParserState {
+    Text,
+    Escape,
+    Charset,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+// Эмуляция одной строки терминала: цвета SGR, возврат каретки и стирание строки
+pub struct AnsiLineBuffer {
+    cells: Vec<(char, Style)>,
+    cursor: usize,
+    style: Style,
+    state: ParserState,
+    params: String,
+}
+
+impl AnsiLineBuffer {
+    pub fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+            cursor: 0,
+            style: Style::default(),
+            state: ParserState::Text,
+            params: String::new(),
+        }
+    }
+
+    // Обрабатывает очередной фрагмент вывода и возвращает завершённые строки
+    pub fn feed(&mut self, text: &str) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+
+        for c in text.chars() {
+            match self.state {
+                ParserState::Text => match c {
+                    '\x1b' => self.state = ParserState::Escape,
+                    '\n' => lines.push(self.take_line()),
+                    '\r' => self.cursor = 0,
+                    '\x08' => self.cursor = self.cursor.saturating_sub(1),
+                    '\t' => {
+                        let next = (self.cursor / TAB_WIDTH + 1) * TAB_WIDTH;
+                        while self.cursor < next {
+                            self.put(' ');
+                        }
+                    }
+                    c if c.is_control() => {}
+                    c => self.put(c),
+                },
+                ParserState::Escape => match c {
+                    '[' => {
+                        self.params.clear();
+                        self.state = ParserState::Csi;
+                    }
+                    ']' => self.state = ParserState::Osc,
+                    '(' | ')' | '*' | '+' => self.state = ParserState::Charset,
+                    _ => self.state = ParserState::Text,
+                },
+                ParserState::Charset => self.state = ParserState::Text,
+                ParserState::Csi => match c {
+                    '\x30'..='\x3f' | '\x20'..='\x2f' => self.params.push(c),
+                    '\x40'..='\x7e' => {
+                        self.dispatch_csi(c);
+                        self.state = ParserState::Text;
+                    }
+                    _ => self.state = ParserState::Text,
+                },
+                ParserState::Osc => match c {
+                    '\x07' => self.state = ParserState::Text,
+                    '\x1b' => self.state = ParserState::OscEscape,
+                    _ => {}
+                },
+                ParserState::OscEscape => self.state = ParserState::Text,
+            }
+        }
+
+        lines
+    }
+
+    // Незавершённая строка (например, строка прогресса pacman)
+    pub fn current(&self) -> Option<Line<'static>> {
+        (!self.cells.is_empty()).then(|| self.build_line())
+    }
+
+    // Завершает вывод, возвращая оставшуюся незавершённую строку
+    pub fn finish(&mut self) -> Option<Line<'static>> {
+        (!self.cells.is_empty()).then(|| self.take_line())
+    }
+
+    fn put(&mut self, c: char) {
+        if self.cursor < self.cells.len() {
+            self.cells[self.cursor] = (c, self.style);
+        } else {
+            while self.cells.len() < self.cursor {
+                self.cells.push((' ', Style::default()));
+            }
+            self.cells.push((c, self.style));
+        }
+        self.cursor += 1;
+    }
+
+    fn take_line(&mut self) -> Line<'static> {
+        let line = self.build_line();
+        self.cells.clear();
+        self.cursor = 0;
+        line
+    }
+
+    fn build_line(&self) -> Line<'static> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        let mut text = String::new();
+        let mut style = None;
+
+        for (c, cell_style) in &self.cells {
+            if style != Some(*cell_style) {
+                if let Some(style) = style {
+                    spans.push(Span::styled(std::mem::take(&mut text), style));
+                }
+                style = Some(*cell_style);
+            }
+            text.push(*c);
+        }
+        if let Some(style) = style {
+            spans.push(Span::styled(text, style));
+        }
+
+        Line::from(spans)
+    }
+
+    fn dispatch_csi(&mut self, action: char) {
+        let params: Vec<usize> = self.params
+            .split(';')
+            .map(|param| param.parse().unwrap_or(0))
+            .collect();
+        let first = params.first().copied().unwrap_or(0);
+
+        match action {
+            'm' => self.apply_sgr(&params),
+            'K' => match first {
+                0 => self.cells.truncate(self.cursor),
+                1 => {
+                    for cell in self.cells.iter_mut().take(self.cursor + 1) {
+                        *cell = (' ', Style::default());
+                    }
+                }
+                _ => self.cells.clear(),
+            },
+            'G' => self.cursor = first.saturating_sub(1),
+            'C' => self.cursor += first.max(1),
+            'D' => self.cursor = self.cursor.saturating_sub(first.max(1)),
+            _ => {}
+        }
+    }
+
+    fn apply_sgr(&mut self, params: &[usize]) {
+        let mut params = params.iter().copied();
+
+        while let Some(param) = params.next() {
+            self.style = match param {
+                0 => Style::default(),
+                1 => self.style.add_modifier(Modifier::BOLD),
+                2 => self.style.add_modifier(Modifier::DIM),
+                3 => self.style.add_modifier(Modifier::ITALIC),
+                4 => self.style.add_modifier(Modifier::UNDERLINED),
+                7 => self.style.add_modifier(Modifier::REVERSED),
+                22 => self.style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+                23 => self.style.remove_modifier(Modifier::ITALIC),
+                24 => self.style.remove_modifier(Modifier::UNDERLINED),
+                27 => self.style.remove_modifier(Modifier::REVERSED),
+                30..=37 => self.style.fg(basic_color(param - 30)),
+                38 => match extended_color(&mut params) {
+                    Some(color) => self.style.fg(color),
+                    None => self.style,
+                },
+                39 => self.style.fg(Color::Reset),
+                40..=47 => self.style.bg(basic_color(param - 40)),
+                48 => match extended_color(&mut params) {
+                    Some(color) => self.style.bg(color),
+                    None => self.style,
+                },
+                49 => self.style.bg(Color::Reset),
+                90..=97 => self.style.fg(basic_color(param - 90 + 8)),
+                100..=107 => self.style.bg(basic_color(param - 100 + 8)),
+                _ => self.style,
+            };
+        }
+    }
+}
+
+impl Default for AnsiLineBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn basic_color(index: usize) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+// 38;5;n и 38;2;r;g;b
+fn extended_color<I>(params: &mut I) -> Option<Color>
+where
+    I: Iterator<Item = usize>,
+{
+    match params.next()? {
+        5 => Some(Color::Indexed(params.next()? as u8)),
+        2 => {
+            let r = params.next()? as u8;
+            let g = params.next()? as u8;
+            let b = params.next()? as u8;
+            Some(Color::Rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    fn feed_text(buffer: &mut AnsiLineBuffer, chunk: &str) -> Vec<String> {
+        buffer.feed(chunk).iter().map(text).collect()
+    }
+
+    #[test]
+    fn complete_and_partial_lines() {
+        let mut buffer = AnsiLineBuffer::new();
+        assert_eq!(feed_text(&mut buffer, "one\ntwo\nthr"), ["one", "two"]);
+        assert_eq!(buffer.current().as_ref().map(text).as_deref(), Some("thr"));
+        assert_eq!(feed_text(&mut buffer, "ee\n"), ["three"]);
+        assert!(buffer.current().is_none());
+        assert!(buffer.finish().is_none());
+    }
+
+    #[test]
+    fn carriage_return_collapses_progress() {
+        let mut buffer = AnsiLineBuffer::new();
+        assert_eq!(feed_text(&mut buffer, " 10% [#-------]\r 50% [####----]\r100% [########]\n"), ["100% [########]"]);
+
+        // Без стирания более короткий текст перекрывает только начало строки, как в терминале
+        feed_text(&mut buffer, "downloading...\rdone");
+        assert_eq!(buffer.current().as_ref().map(text).as_deref(), Some("doneloading..."));
+        assert_eq!(buffer.finish().as_ref().map(text).as_deref(), Some("doneloading..."));
+        assert!(buffer.current().is_none());
+    }
+
+    #[test]
+    fn erase_in_line() {
+        let mut buffer = AnsiLineBuffer::new();
+        assert_eq!(feed_text(&mut buffer, "downloading...\r\x1b[Kdone\n"), ["done"]);
+        assert_eq!(feed_text(&mut buffer, "abcdef\x1b[3D\x1b[0K\n"), ["abc"]);
+        assert_eq!(feed_text(&mut buffer, "abcdef\x1b[3D\x1b[1K\n"), ["    ef"]);
+        assert_eq!(feed_text(&mut buffer, "abcdef\x1b[2Kx\n"), ["      x"]);
+    }
+
+    #[test]
+    fn cursor_movement() {
+        let mut buffer = AnsiLineBuffer::new();
+        assert_eq!(feed_text(&mut buffer, "hello\x1b[2GX\n"), ["hXllo"]);
+        assert_eq!(feed_text(&mut buffer, "hello\x1b[GX\n"), ["Xello"]);
+        assert_eq!(feed_text(&mut buffer, "ab\x1b[3Cc\n"), ["ab   c"]);
+        assert_eq!(feed_text(&mut buffer, "ab\x1b[Cc\n"), ["ab c"]);
+        assert_eq!(feed_text(&mut buffer, "hello\x1b[2DX\n"), ["helXo"]);
+        assert_eq!(feed_text(&mut buffer, "hi\x1b[10DX\n"), ["Xi"]);
+        assert_eq!(feed_text(&mut buffer, "ab\x08X\ta\n"), ["aX      a"]);
+    }
+
+    #[test]
+    fn sgr_colors() {
+        let mut buffer = AnsiLineBuffer::new();
+        let lines = buffer.feed("\x1b[1;31mError\x1b[0m: \x1b[38;5;208mdisk\x1b[39m \x1b[48;2;1;2;3mfull\x1b[m\n");
+        let spans: Vec<(&str, Style)> = lines[0].spans.iter().map(|span| (span.content.as_ref(), span.style)).collect();
+
+        assert_eq!(spans, [
+            ("Error", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            (": ", Style::default()),
+            ("disk", Style::default().fg(Color::Indexed(208))),
+            (" ", Style::default().fg(Color::Reset)),
+            ("full", Style::default().fg(Color::Reset).bg(Color::Rgb(1, 2, 3))),
+        ]);
+    }
+
+    #[test]
+    fn bright_colors_and_attribute_reset() {
+        let mut buffer = AnsiLineBuffer::new();
+        let lines = buffer.feed("\x1b[92;1mok\x1b[22m!\n");
+        assert_eq!(lines[0].spans[0].style, Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD));
+        assert_eq!(lines[0].spans[1].style, Style::default().fg(Color::LightGreen).remove_modifier(Modifier::BOLD | Modifier::DIM));
+    }
+
+    #[test]
+    fn escape_split_across_chunks() {
+        let mut buffer = AnsiLineBuffer::new();
+        assert!(buffer.feed("\x1b").is_empty());
+        assert!(buffer.feed("[3").is_empty());
+        assert!(buffer.feed("2mok\x1b[").is_empty());
+        let lines = buffer.feed("0m!\n");
+
+        assert_eq!(text(&lines[0]), "ok!");
+        assert_eq!(lines[0].spans[0].style, Style::default().fg(Color::Green));
+        assert_eq!(lines[0].spans[1].style, Style::default());
+
+        // Стирание строки, разбитое посередине, после возврата каретки
+        feed_text(&mut buffer, "50%\r\x1b[");
+        assert_eq!(feed_text(&mut buffer, "K100%\n"), ["100%"]);
+    }
+
+    #[test]
+    fn osc_and_charset_sequences_are_skipped() {
+        let mut buffer = AnsiLineBuffer::new();
+        assert_eq!(feed_text(&mut buffer, "\x1b]0;title\x07\x1b(Bplain\x1b]2;other\x1b\\ text\n"), ["plain text"]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use chrono::{Local, NaiveDateTime};
+use thiserror::Error;
+use crate::config::xdg_dir;
+use crate::plan;
+
+// configs-20261017-153000-update.tar.zst
+const FILE_PREFIX: &str = "configs-";
+const FILE_EXTENSION: &str = ".tar.zst";
+const TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+// update.sh удаляет настройки так же, как uninstall.sh full
+pub const UPDATE_REASON: &str = "update";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("cannot determine the backup directory")]
+    Dir,
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("tar: {0}")]
+    Tar(String),
+}
+
+// Архив настроек, снятый перед удалением или обновлением
+pub struct Backup {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+    // Запуск, перед которым снят архив: update, uninstall-config, uninstall-full
+    pub reason: String,
+    pub size: u64,
+}
+
+// Архивирует существующие пути из remove_config; None - архивировать нечего
+pub fn create(root: &Path, home: &Path, reason: &str) -> Result<Option<PathBuf>, BackupError> {
+    let members = existing_members(root, home);
+    if members.is_empty() {
+        return Ok(None);
+    }
+
+    let path = archive_path(reason).ok_or(BackupError::Dir)?;
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    fs::create_dir_all(dir).map_err(|source| BackupError::Io { path: dir.to_path_buf(), source })?;
+
+    // Недописанный архив не попадает в список: имя без префикса
+    let partial = dir.join(format!(".{}.part", path.file_name().unwrap_or_default().to_string_lossy()));
+    let mut args = vec!["--zstd".to_string(), "-cf".to_string(), partial.display().to_string()];
+    args.extend(["-C".to_string(), "/".to_string()]);
+    args.extend(members);
+
+    if let Err(e) = tar(&args) {
+        fs::remove_file(&partial).unwrap_or_default();
+        return Err(e);
+    }
+    fs::rename(&partial, &path).map_err(|source| BackupError::Io { path: path.clone(), source })?;
+    Ok(Some(path))
+}
+
+// Имя архива, который создаст create; None, если архивировать нечего
+pub fn planned(root: &Path, home: &Path, reason: &str) -> Option<PathBuf> {
+    match existing_members(root, home).is_empty() {
+        true => None,
+        false => archive_path(reason),
+    }
+}
+
+// Архивы, новые сверху
+pub fn list() -> Vec<Backup> {
+    let Some(entries) = backup_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<Backup> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| read_backup(entry.path()))
+        .collect();
+
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+    backups
+}
+
+// Сохранённые в архиве пути верхнего уровня, например /home/user/.config/sway
+pub fn contents(archive: &Path) -> Result<Vec<PathBuf>, BackupError> {
+    let output = tar(&["--zstd".to_string(), "-tf".to_string(), archive.display().to_string()])?;
+    let members: Vec<&str> = output.lines()
+        .map(|line| line.trim_end_matches('/'))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    // Каталог архивируется вместе с содержимым, поэтому вложенные пути пропускаются
+    let top_level = members.iter()
+        .filter(|member| !members.iter().any(|parent| {
+            member.strip_prefix(*parent).is_some_and(|rest| rest.starts_with('/'))
+        }))
+        .map(|member| Path::new("/").join(member))
+        .collect();
+    Ok(top_level)
+}
+
+// Аргументы sudo для восстановления: файлы в /etc принадлежат root, владельцы сохраняются из архива
+pub fn restore_args(archive: &Path, paths: &[PathBuf]) -> Vec<String> {
+    let mut args = vec![
+        "tar".to_string(),
+        "--zstd".to_string(),
+        "-xpvf".to_string(),
+        archive.display().to_string(),
+        "-C".to_string(),
+        "/".to_string(),
+    ];
+    args.extend(paths.iter().map(|path| member(path)));
+    args
+}
+
+pub fn delete(archive: &Path) -> Result<(), BackupError> {
+    fs::remove_file(archive).map_err(|source| BackupError::Io { path: archive.to_path_buf(), source })
+}
+
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1048576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1048576.0),
+    }
+}
+
+pub fn backup_dir() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_DATA_HOME", ".local/share")?.join("kite-tools/backups"))
+}
+
+fn archive_path(reason: &str) -> Option<PathBuf> {
+    let name = format!("{}{}-{}{}", FILE_PREFIX, Local::now().format(TIME_FORMAT), reason, FILE_EXTENSION);
+    Some(backup_dir()?.join(name))
+}
+
+fn existing_members(root: &Path, home: &Path) -> Vec<String> {
+    plan::config_paths(root, home).iter()
+        .filter(|path| path.symlink_metadata().is_ok())
+        .map(|path| member(path))
+        .collect()
+}
+
+// Пути в архиве хранятся относительно /
+fn member(path: &Path) -> String {
+    path.strip_prefix("/").unwrap_or(path).display().to_string()
+}
+
+fn read_backup(path: PathBuf) -> Option<Backup> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_EXTENSION)?;
+    let (time, reason) = stem.split_at_checked(15)?;
+    let created = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+    let size = path.metadata().ok()?.len();
+
+    Some(Backup {
+        reason: reason.trim_start_matches('-').to_string(),
+        path,
+        created,
+        size,
+    })
+}
+
+fn tar(args: &[String]) -> Result<String, BackupError> {
+    let output = Command::new("tar")
+        .args(args)
+        .output()
+        .map_err(|e| BackupError::Tar(e.to_string()))?;
+
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let error = stderr.lines().next().unwrap_or_default();
+            Err(BackupError::Tar(error.trim_start_matches("tar: ").to_string()))
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use clap::ValueEnum;
+
+// Канал сборки системы (BUILD_ID)
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Channel {
+    Stable,
+    Developer,
+    Experimental,
+}
+
+impl Channel {
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Developer => "developer",
+            Channel::Experimental => "experimental",
+        }
+    }
+
+    // Канал установленной системы хранится в BUILD_ID
+    pub fn from_build_id(build_id: &str) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|channel| channel.as_arg() == build_id)
+    }
+
+    pub fn title_key(&self) -> &'static str {
+        match self {
+            Channel::Stable => "installation_types_stable",
+            Channel::Developer => "installation_types_developer",
+            Channel::Experimental => "installation_types_experimental",
+        }
+    }
+
+    pub fn description_key(&self) -> &'static str {
+        match self {
+            Channel::Stable => "installation_types_description_stable",
+            Channel::Developer => "installation_types_description_developer",
+            Channel::Experimental => "installation_types_description_experimental",
+        }
+    }
+
+    // Чем больше, тем стабильнее канал: переход к более стабильному может откатить изменения
+    pub fn stability(&self) -> u8 {
+        match self {
+            Channel::Experimental => 0,
+            Channel::Developer => 1,
+            Channel::Stable => 2,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use crate::config::xdg_dir;
+use crate::summary::UpdateCheckRecord;
+use crate::update_check::UpdateCheckResult;
+
+const CACHE_FILE: &str = "update-check.json";
+
+// Коды выхода `kite-tools check`
+pub const EXIT_UP_TO_DATE: i32 = 0;
+pub const EXIT_UPDATE_AVAILABLE: i32 = 10;
+pub const EXIT_CHECK_FAILED: i32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    UpToDate,
+    Available,
+    Ahead,
+    Error,
+}
+
+// Итог проверки обновлений: вывод `kite-tools check --json` и содержимое кэша для главного меню
+#[derive(Serialize, Deserialize)]
+pub struct CheckReport {
+    pub status: CheckStatus,
+    pub channel: Option<String>,
+    pub current_version: Option<String>,
+    pub latest_version: Option<String>,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Local>,
+}
+
+impl CheckReport {
+    pub fn new(result: &UpdateCheckResult, channel: Option<&str>, current_version: Option<&str>) -> Self {
+        let (status, latest_version, error) = match result {
+            UpdateCheckResult::UpToDate => (CheckStatus::UpToDate, current_version.map(str::to_string), None),
+            UpdateCheckResult::Available { version } => (CheckStatus::Available, Some(version.clone()), None),
+            UpdateCheckResult::Ahead { version } => (CheckStatus::Ahead, Some(version.clone()), None),
+            UpdateCheckResult::Error(e) => (CheckStatus::Error, None, Some(e.clone())),
+        };
+
+        Self {
+            status,
+            channel: channel.map(str::to_string),
+            current_version: current_version.map(str::to_string),
+            latest_version,
+            error,
+            checked_at: Local::now(),
+        }
+    }
+
+    pub fn record(&self) -> UpdateCheckRecord {
+        let latest_version = self.latest_version.clone().unwrap_or_default();
+        let result = match self.status {
+            CheckStatus::UpToDate => UpdateCheckResult::UpToDate,
+            CheckStatus::Available => UpdateCheckResult::Available { version: latest_version },
+            CheckStatus::Ahead => UpdateCheckResult::Ahead { version: latest_version },
+            CheckStatus::Error => UpdateCheckResult::Error(self.error.clone().unwrap_or_default()),
+        };
+
+        UpdateCheckRecord {
+            result,
+            checked_at: self.checked_at,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self.status {
+            CheckStatus::UpToDate | CheckStatus::Ahead => EXIT_UP_TO_DATE,
+            CheckStatus::Available => EXIT_UPDATE_AVAILABLE,
+            CheckStatus::Error => EXIT_CHECK_FAILED,
+        }
+    }
+}
+
+// Кэш устаревает, когда система обновлена или переведена на другой канал
+pub fn load(channel: Option<&str>, current_version: Option<&str>) -> Option<CheckReport> {
+    let content = fs::read_to_string(cache_path()?).ok()?;
+    let report: CheckReport = serde_json::from_str(&content).ok()?;
+
+    let actual = report.channel.as_deref() == channel && report.current_version.as_deref() == current_version;
+    actual.then_some(report)
+}
+
+pub fn save(report: &CheckReport) -> io::Result<()> {
+    let path = cache_path().ok_or(io::Error::from(io::ErrorKind::NotFound))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let content = serde_json::to_string_pretty(report).map_err(io::Error::other)?;
+    fs::write(path, content)
+}
+
+fn cache_path() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_CACHE_HOME", ".cache")?.join("kite-tools").join(CACHE_FILE))
+}
@@ -0,0 +1,178 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use thiserror::Error;
+use crate::localization::Language;
+
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/kite-tools/config.toml";
+pub const SCRIPTS_DIR_ENV: &str = "KITE_TOOLS_SCRIPTS_DIR";
+
+const DEFAULT_SCRIPTS_DIR: &str = "/usr/src/kite-tools";
+const DEFAULT_SYSTEM_ROOT: &str = "/";
+const DEFAULT_LANGUAGE: Language = Language::Russian;
+const DEFAULT_GITHUB_OWNER: &str = "BleynChannel";
+const DEFAULT_GITHUB_REPO: &str = "Kite-Dots";
+const PACKAGE_LIST_FILE: &str = "custom_apps.lst";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{}: {source}", path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("{}: {source}", path.display())]
+    Parse { path: PathBuf, source: toml::de::Error },
+}
+
+// Файл настроек: заданные поля перекрывают значения предыдущего файла
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    scripts_dir: Option<PathBuf>,
+    system_root: Option<PathBuf>,
+    package_list: Option<PathBuf>,
+    language: Option<ConfigLanguage>,
+    github: Option<GithubConfig>,
+    release_source: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GithubConfig {
+    owner: Option<String>,
+    repo: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum ConfigLanguage {
+    Ru,
+    En,
+}
+
+impl From<ConfigLanguage> for Language {
+    fn from(language: ConfigLanguage) -> Self {
+        match language {
+            ConfigLanguage::Ru => Language::Russian,
+            ConfigLanguage::En => Language::English,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub scripts_dir: PathBuf,
+    // Корень, от которого читается состояние системы (etc/os-release и т.д.)
+    pub system_root: PathBuf,
+    package_list: Option<PathBuf>,
+    pub language: Language,
+    pub github_owner: String,
+    pub github_repo: String,
+    // Замена GitHub для проверки обновлений: URL или каталог
+    pub release_source: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            scripts_dir: PathBuf::from(DEFAULT_SCRIPTS_DIR),
+            system_root: PathBuf::from(DEFAULT_SYSTEM_ROOT),
+            package_list: None,
+            language: DEFAULT_LANGUAGE,
+            github_owner: DEFAULT_GITHUB_OWNER.to_string(),
+            github_repo: DEFAULT_GITHUB_REPO.to_string(),
+            release_source: None,
+        }
+    }
+}
+
+impl Config {
+    // Порядок: значения по умолчанию, /etc/kite-tools/config.toml,
+    // ~/.config/kite-tools/config.toml, затем --scripts-dir или KITE_TOOLS_SCRIPTS_DIR
+    pub fn load(scripts_dir: Option<PathBuf>) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+
+        let paths = [Some(PathBuf::from(SYSTEM_CONFIG_PATH)), user_config_path()];
+        for path in paths.into_iter().flatten() {
+            if path.is_file() {
+                config.apply(&path)?;
+            }
+        }
+
+        if let Some(scripts_dir) = scripts_dir {
+            config.scripts_dir = scripts_dir;
+        }
+
+        Ok(config)
+    }
+
+    fn apply(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let content = fs::read_to_string(path)
+            .map_err(|source| ConfigError::Read { path: path.to_path_buf(), source })?;
+        let file: ConfigFile = toml::from_str(&content)
+            .map_err(|source| ConfigError::Parse { path: path.to_path_buf(), source })?;
+
+        // Относительные пути считаются от каталога файла настроек
+        let base = path.parent().unwrap_or(Path::new("/"));
+        if let Some(scripts_dir) = file.scripts_dir {
+            self.scripts_dir = base.join(scripts_dir);
+        }
+        if let Some(system_root) = file.system_root {
+            self.system_root = base.join(system_root);
+        }
+        if let Some(package_list) = file.package_list {
+            self.package_list = Some(base.join(package_list));
+        }
+        if let Some(language) = file.language {
+            self.language = language.into();
+        }
+        if let Some(github) = file.github {
+            if let Some(owner) = github.owner {
+                self.github_owner = owner;
+            }
+            if let Some(repo) = github.repo {
+                self.github_repo = repo;
+            }
+        }
+
+        if let Some(release_source) = file.release_source {
+            self.release_source = Some(release_source);
+        }
+
+        Ok(())
+    }
+
+    pub fn script_path(&self, name: &str) -> String {
+        self.scripts_dir.join(name).to_string_lossy().into_owned()
+    }
+
+    // По умолчанию список пакетов лежит рядом со скриптами
+    pub fn package_list(&self) -> PathBuf {
+        self.package_list.clone()
+            .unwrap_or_else(|| self.scripts_dir.join(PACKAGE_LIST_FILE))
+    }
+
+    // Переменные окружения для скриптов в виде VAR=value, понятном sudo и env.
+    // Передаются только при переопределённом репозитории, чтобы не требовать SETENV в sudoers
+    pub fn script_env(&self) -> Vec<String> {
+        if self.github_owner == DEFAULT_GITHUB_OWNER && self.github_repo == DEFAULT_GITHUB_REPO {
+            return Vec::new();
+        }
+
+        vec![
+            format!("GITHUB_USER={}", self.github_owner),
+            format!("GITHUB_REPO={}", self.github_repo),
+        ]
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_CONFIG_HOME", ".config")?.join("kite-tools").join("config.toml"))
+}
+
+// Каталог XDG из переменной окружения, а если она не задана или пуста - из $HOME/<fallback>
+pub fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    match env::var_os(var) {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => Some(PathBuf::from(env::var_os("HOME")?).join(fallback)),
+    }
+}
@@ -0,0 +1,263 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::Local;
+use crate::config::xdg_dir;
+
+// Журналы пишутся в /var/log при запуске от root, иначе в каталог состояния XDG
+const SYSTEM_LOG_DIR: &str = "/var/log/kite-tools";
+
+const COMMAND_KEY: &str = "# command: ";
+const STARTED_KEY: &str = "# started: ";
+const EXIT_CODE_KEY: &str = "# exit code: ";
+const SIGNAL_KEY: &str = "# signal: ";
+const DURATION_KEY: &str = "# duration: ";
+
+struct RunLogState {
+    file: File,
+    at_line_start: bool,
+}
+
+// Журнал одного запуска, может писаться из нескольких потоков
+#[derive(Clone)]
+pub struct RunLog {
+    state: Arc<Mutex<RunLogState>>,
+    started: Instant,
+}
+
+impl RunLog {
+    // Журналирование не должно мешать запуску, поэтому ошибки создания игнорируются
+    pub fn create(program: &str, args: &[String]) -> Option<Self> {
+        let now = Local::now();
+        let file_name = format!("{}-{}", now.format("%Y%m%d-%H%M%S"), log_name(program, args));
+
+        let mut file = log_dirs().into_iter()
+            .find_map(|dir| create_log_file(&dir, &file_name).ok())?;
+
+        writeln!(file, "{}{} {}", COMMAND_KEY, program, args.join(" ")).ok()?;
+        writeln!(file, "{}{}", STARTED_KEY, now.format("%Y-%m-%d %H:%M:%S")).ok()?;
+
+        Some(Self {
+            state: Arc::new(Mutex::new(RunLogState { file, at_line_start: true })),
+            started: Instant::now(),
+        })
+    }
+
+    pub fn write(&self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        if let Ok(mut state) = self.state.lock() {
+            state.file.write_all(data).unwrap_or_default();
+            state.at_line_start = data.ends_with(b"\n");
+        }
+    }
+
+    pub fn finish(&self, exit_code: Option<i32>, signal: Option<i32>) {
+        if let Ok(mut state) = self.state.lock() {
+            if !state.at_line_start {
+                writeln!(state.file).unwrap_or_default();
+            }
+
+            let exit_code = exit_code.map_or("-".to_string(), |code| code.to_string());
+            writeln!(state.file, "{}{}", EXIT_CODE_KEY, exit_code).unwrap_or_default();
+            if let Some(signal) = signal {
+                writeln!(state.file, "{}{}", SIGNAL_KEY, signal).unwrap_or_default();
+            }
+            writeln!(state.file, "{}{}s", DURATION_KEY, self.started.elapsed().as_secs()).unwrap_or_default();
+            state.at_line_start = true;
+        }
+    }
+}
+
+pub struct RunRecord {
+    pub path: PathBuf,
+    pub command: String,
+    pub started: String,
+    // None - запуск не завершился (например, программа была закрыта)
+    pub exit_code: Option<Option<i32>>,
+    pub signal: Option<i32>,
+    pub duration: Option<String>,
+}
+
+// Прошлые запуски, новые сверху
+pub fn list() -> Vec<RunRecord> {
+    let mut records: Vec<RunRecord> = log_dirs().into_iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(Result::ok))
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| read_record(path).ok())
+        .collect();
+
+    records.sort_by(|a, b| b.started.cmp(&a.started));
+    records
+}
+
+pub fn read_output(path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path)
+}
+
+// Имя журнала по скрипту; переменные окружения из script_env перед скриптом в имя не попадают
+fn log_name(program: &str, args: &[String]) -> String {
+    args.iter()
+        .find(|arg| !arg.contains('='))
+        .and_then(|arg| Path::new(arg).file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| program.to_string())
+}
+
+fn read_record(path: PathBuf) -> io::Result<RunRecord> {
+    let file = File::open(&path)?;
+    let mut record = RunRecord {
+        path,
+        command: String::new(),
+        started: String::new(),
+        exit_code: None,
+        signal: None,
+        duration: None,
+    };
+
+    let lines: Vec<String> = BufReader::new(file).split(b'\n')
+        .map_while(Result::ok)
+        .map(|line| String::from_utf8_lossy(&line).into_owned())
+        .collect();
+
+    // Заголовок - первые две строки в порядке записи create; строки вывода с теми же префиксами не учитываются
+    let mut header_len = 0;
+    if let Some(command) = lines.first().and_then(|line| line.strip_prefix(COMMAND_KEY)) {
+        record.command = command.to_string();
+        header_len = 1;
+        if let Some(started) = lines.get(1).and_then(|line| line.strip_prefix(STARTED_KEY)) {
+            record.started = started.to_string();
+            header_len = 2;
+        }
+    }
+
+    // Итог finish - последние строки файла: код выхода, сигнал, если был, и длительность
+    let mut trailer = lines[header_len..].iter().rev();
+    if let Some(duration) = trailer.next().and_then(|line| line.strip_prefix(DURATION_KEY)) {
+        let mut line = trailer.next();
+        let signal = line.and_then(|line| line.strip_prefix(SIGNAL_KEY));
+        if signal.is_some() {
+            line = trailer.next();
+        }
+        if let Some(exit_code) = line.and_then(|line| line.strip_prefix(EXIT_CODE_KEY)) {
+            record.exit_code = Some(exit_code.trim().parse().ok());
+            record.signal = signal.and_then(|signal| signal.trim().parse().ok());
+            record.duration = Some(duration.trim().to_string());
+        }
+    }
+
+    Ok(record)
+}
+
+fn log_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(SYSTEM_LOG_DIR)];
+    if let Some(dir) = xdg_dir("XDG_STATE_HOME", ".local/state") {
+        dirs.push(dir.join("kite-tools/logs"));
+    }
+    dirs
+}
+
+fn create_log_file(dir: &Path, file_name: &str) -> io::Result<File> {
+    fs::create_dir_all(dir)?;
+
+    // Несколько запусков в одну секунду получают суффикс
+    let mut suffix = 0;
+    loop {
+        let path = match suffix {
+            0 => dir.join(format!("{}.log", file_name)),
+            _ => dir.join(format!("{}-{}.log", file_name, suffix)),
+        };
+
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && suffix < 100 => suffix += 1,
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempTree;
+
+    const HEADER: &str = "# command: sudo KITE_REPO=kite /usr/src/kite-tools/update.sh --version v1.2.0\n# started: 2026-10-17 12:00:05\n";
+
+    fn read_log(content: &str) -> RunRecord {
+        let tree = TempTree::new(&[("run.log", content)]);
+        read_record(tree.path().join("run.log")).unwrap()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn log_name_skips_env_assignments() {
+        assert_eq!(log_name("sudo", &args(&["KITE_REPO=kite", "KITE_OWNER=a=b", "/usr/src/kite-tools/update.sh", "--version"])), "update");
+        assert_eq!(log_name("bash", &args(&["/usr/src/kite-tools/install.sh"])), "install");
+        assert_eq!(log_name("pacman", &[]), "pacman");
+    }
+
+    #[test]
+    fn finished_run() {
+        let record = read_log(&format!("{}[INFO] Checking system...\ndone\n# exit code: 0\n# duration: 42s\n", HEADER));
+        assert_eq!(record.command, "sudo KITE_REPO=kite /usr/src/kite-tools/update.sh --version v1.2.0");
+        assert_eq!(record.started, "2026-10-17 12:00:05");
+        assert_eq!(record.exit_code, Some(Some(0)));
+        assert_eq!(record.signal, None);
+        assert_eq!(record.duration.as_deref(), Some("42s"));
+    }
+
+    #[test]
+    fn run_killed_by_signal() {
+        let record = read_log(&format!("{}output\n# exit code: -\n# signal: 15\n# duration: 3s\n", HEADER));
+        assert_eq!(record.exit_code, Some(None));
+        assert_eq!(record.signal, Some(15));
+        assert_eq!(record.duration.as_deref(), Some("3s"));
+    }
+
+    #[test]
+    fn header_without_trailer() {
+        // Программа была закрыта во время запуска: итог не записан
+        let record = read_log(&format!("{}[INFO] Removing old version...\npartial output", HEADER));
+        assert_eq!(record.started, "2026-10-17 12:00:05");
+        assert_eq!(record.exit_code, None);
+        assert_eq!(record.signal, None);
+        assert_eq!(record.duration, None);
+    }
+
+    #[test]
+    fn output_lines_are_not_parsed() {
+        let record = read_log(&format!("{}# command: echo fake\n# exit code: 3\n# duration: 1s\nstill running\n", HEADER));
+        assert_eq!(record.command, "sudo KITE_REPO=kite /usr/src/kite-tools/update.sh --version v1.2.0");
+        assert_eq!(record.exit_code, None);
+        assert_eq!(record.duration, None);
+
+        // Длительность без кода выхода перед ней итогом не считается
+        let record = read_log(&format!("{}# duration: 1s\n", HEADER));
+        assert_eq!(record.exit_code, None);
+        assert_eq!(record.duration, None);
+    }
+
+    #[test]
+    fn trailer_without_output() {
+        let record = read_log(&format!("{}# exit code: 1\n# duration: 0s\n", HEADER));
+        assert_eq!(record.exit_code, Some(Some(1)));
+        assert_eq!(record.duration.as_deref(), Some("0s"));
+    }
+
+    #[test]
+    fn empty_log() {
+        let record = read_log("");
+        assert!(record.command.is_empty());
+        assert!(record.started.is_empty());
+        assert_eq!(record.exit_code, None);
+    }
+}
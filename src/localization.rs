@@ -21,11 +21,35 @@ impl Localization {
         strings.insert("package_list_title", ["Выбор пакетов для установки", "Select packages to install"]);
         strings.insert("available_packages", ["Доступные пакеты", "Available packages"]);
 
+        // System summary
+        strings.insert("summary_title", ["Система", "System"]);
+        strings.insert("summary_system", ["ОС", "OS"]);
+        strings.insert("summary_state", ["Коршун", "Kite"]);
+        strings.insert("summary_installed", ["Установлен", "Installed"]);
+        strings.insert("summary_not_installed", ["Не установлен", "Not installed"]);
+        strings.insert("summary_not_detected", ["Не удалось прочитать os-release", "Cannot read os-release"]);
+        strings.insert("summary_channel", ["Канал", "Channel"]);
+        strings.insert("summary_version", ["Версия", "Version"]);
+        strings.insert("summary_backup", ["Копия os-release", "os-release backup"]);
+        strings.insert("summary_backup_present", ["Есть", "Present"]);
+        strings.insert("summary_backup_missing", ["Нет", "Missing"]);
+        strings.insert("summary_kernel", ["Ядро", "Kernel"]);
+        strings.insert("summary_last_check", ["Проверка обновлений", "Update check"]);
+        strings.insert("summary_check_never", ["Не выполнялась", "Not run yet"]);
+        strings.insert("summary_check_up_to_date", ["Актуальная версия", "Up to date"]);
+        strings.insert("summary_check_available", ["Доступна {}", "{} available"]);
+        strings.insert("summary_check_ahead", ["Установлена более новая", "Installed is newer"]);
+        strings.insert("summary_check_failed", ["Ошибка", "Failed"]);
+
         // Menu items
         strings.insert("menu_install", ["Установка системы", "System Installation"]);
         strings.insert("menu_update", ["Обновление системы", "System Update"]);
+        strings.insert("menu_versions", ["Версии системы", "System Versions"]);
+        strings.insert("menu_switch_channel", ["Смена канала", "Switch Channel"]);
         strings.insert("menu_uninstall", ["Очистка системы", "System Uninstall"]);
         strings.insert("menu_install_package", ["Установка пакетов", "Install Packages"]);
+        strings.insert("menu_restore", ["Восстановление настроек", "Restore Configs"]);
+        strings.insert("menu_history", ["История запусков", "History"]);
 
         // Menu status
         strings.insert("welcome_menu_status", ["Добро пожаловать в инструменты управления Коршун", "Welcome to Kite Tools"]);
@@ -68,10 +92,186 @@ impl Localization {
         // Update check
         strings.insert("update_check_title", ["Проверка обновлений", "Update Check"]);
         strings.insert("available_updates", ["Доступные обновления", "Available Updates"]);
+        strings.insert("update_check_channel", ["Канал: {}", "Channel: {}"]);
+        strings.insert("update_check_current", ["Текущая версия: {}", "Current version: {}"]);
+        strings.insert("update_check_running", ["Проверка обновлений...", "Checking for updates..."]);
+        strings.insert("update_check_error", ["Не удалось проверить обновления: {}", "Failed to check for updates: {}"]);
+        strings.insert("update_check_interrupted", ["проверка прервана", "check interrupted"]);
+        strings.insert("check_update_available", ["Доступна новая версия {}", "New version {} is available"]);
+        strings.insert("check_cache_error", ["Не удалось сохранить результат проверки: {}", "Failed to save the check result: {}"]);
+        strings.insert("package_list_error", ["Не удалось получить состояние пакетов: {}", "Failed to get the package status: {}"]);
+        strings.insert("timer_installed", [
+            "Таймер проверки обновлений установлен в {} (интервал {})",
+            "Update check timer installed to {} (interval {})"]);
+        strings.insert("timer_removed", ["Таймер проверки обновлений удалён", "Update check timer removed"]);
+        strings.insert("timer_error", ["Не удалось настроить таймер: {}", "Failed to configure the timer: {}"]);
+        strings.insert("unknown_channel", ["Неизвестный канал системы в BUILD_ID: {}", "Unknown system channel in BUILD_ID: {}"]);
+
+        // Versions
+        strings.insert("versions_title", ["Версии: {}", "Versions: {}"]);
+        strings.insert("versions_list", ["Доступные версии", "Available versions"]);
+        strings.insert("versions_details", ["Описание", "Details"]);
+        strings.insert("versions_loading", ["Загрузка списка версий...", "Loading versions..."]);
+        strings.insert("versions_empty", ["Нет доступных версий", "No versions available"]);
+        strings.insert("versions_prerelease", ["Предварительный выпуск", "Pre-release"]);
+        strings.insert("versions_no_notes", ["Описание отсутствует", "No description"]);
+        strings.insert("changelog_title", ["Изменения", "Changes"]);
+        strings.insert("changelog_unavailable", ["Не удалось загрузить список изменений", "Failed to load the list of changes"]);
+        strings.insert("changelog_no_commits", ["Нет сведений о коммитах", "No commit information"]);
+        strings.insert("changelog_rollback", ["Будут отменены коммиты:", "These commits will be rolled back:"]);
+        strings.insert("changelog_hints", [
+            "↑/↓/PgUp/PgDn: Прокрутка | Enter: Подтвердить | Esc: Отменить",
+            "↑/↓/PgUp/PgDn: Scroll | Enter: Confirm | Esc: Cancel"]);
+        strings.insert("versions_load_error", ["Не удалось получить список версий: {}", "Failed to load versions: {}"]);
+
+        // Pre-flight checks
+        strings.insert("preflight_title", ["Предварительные проверки", "Pre-flight checks"]);
+        strings.insert("preflight_failed", ["Предварительные проверки не пройдены:\n{}", "Pre-flight checks failed:\n{}"]);
+        strings.insert("preflight_running", [
+            "Выполняются предварительные проверки...\n\nEsc - отменить",
+            "Running pre-flight checks...\n\nEsc - cancel"]);
+        strings.insert("preflight_failed_lock_stale", [
+            "Предварительные проверки не пройдены, список ниже. База pacman заблокирована устаревшим db.lck (создан {} назад). Удалить блокировку?",
+            "Pre-flight checks failed, see the list below. The pacman database is locked by a stale db.lck ({} old). Remove the lock?"]);
+        strings.insert("preflight_failed_lock_held", [
+            "Предварительные проверки не пройдены, список ниже. База pacman заблокирована процессом {} (PID {}). Дождаться его завершения?",
+            "Pre-flight checks failed, see the list below. The pacman database is locked by {} (PID {}). Wait for it to finish?"]);
+        strings.insert("preflight_pass", ["норма", "pass"]);
+        strings.insert("preflight_warn", ["внимание", "warn"]);
+        strings.insert("preflight_fail", ["ошибка", "fail"]);
+        strings.insert("preflight_disk", ["Место в {}", "Free space on {}"]);
+        strings.insert("preflight_disk_free", ["Свободно {} (рекомендуется от {})", "{} free (at least {} recommended)"]);
+        strings.insert("preflight_disk_unknown", ["Не удалось определить раздел", "Cannot determine the partition"]);
+        strings.insert("preflight_pacman", ["База pacman", "Pacman database"]);
+        strings.insert("preflight_pacman_free", ["Не заблокирована", "Not locked"]);
+        strings.insert("preflight_pacman_busy", ["Заблокирована: выполняется {} (PID {})", "Locked: {} is running (PID {})"]);
+        strings.insert("preflight_pacman_stale", ["Заблокирована, но pacman, yay и paru не запущены: устаревший db.lck ({})", "Locked, but no pacman, yay or paru is running: stale db.lck ({} old)"]);
+        strings.insert("preflight_pacman_running", ["Выполняется {} (PID {})", "{} is running (PID {})"]);
+        strings.insert("preflight_github", ["GitHub", "GitHub"]);
+        strings.insert("preflight_github_not_found", ["Репозиторий не найден: {}", "Repository not found: {}"]);
+        strings.insert("preflight_github_status", ["Неожиданный ответ: HTTP {}", "Unexpected response: HTTP {}"]);
+        strings.insert("preflight_github_unreachable", ["Недоступен: {}", "Unreachable: {}"]);
+        strings.insert("preflight_sudo", ["sudo", "sudo"]);
+        strings.insert("preflight_sudo_root", ["Запущено от root", "Running as root"]);
+        strings.insert("preflight_sudo_missing", ["sudo не найден", "sudo not found"]);
+        strings.insert("preflight_power", ["Питание", "Power"]);
+        strings.insert("preflight_power_no_battery", ["Нет батареи", "No battery"]);
+        strings.insert("preflight_power_ac", ["От сети", "On AC power"]);
+        strings.insert("preflight_power_battery", ["От батареи, заряд {}%", "On battery, {}% charged"]);
+        strings.insert("preflight_power_low", ["От батареи, заряд {}% (нужно от {}%)", "On battery, {}% charged (at least {}% required)"]);
+        strings.insert("preflight_tools", ["Программы", "Tools"]);
+        strings.insert("preflight_tools_missing", ["Не найдены: {}", "Not found: {}"]);
+        strings.insert("preflight_tools_will_install", ["Не найдены: {}, будут установлены", "Not found: {}, will be installed"]);
+
+        // Dry run
+        strings.insert("plan_title", ["План", "Plan"]);
+        strings.insert("plan_dry_run", ["Пробный запуск: ничего не изменено", "Dry run: nothing has been changed"]);
+        strings.insert("plan_preview_continue", [
+            "Предпросмотр: ничего не изменено. Перейти к выполнению?",
+            "Preview: nothing has been changed. Proceed to the real run?"]);
+        strings.insert("plan_command", ["Команда", "Command"]);
+        strings.insert("plan_steps", ["Шаги скрипта", "Script steps"]);
+        strings.insert("plan_install", ["Будут установлены", "Packages to install"]);
+        strings.insert("plan_package_script", [
+            "и пакеты из install.sh Коршуна: список известен только после загрузки",
+            "plus the packages of the Kite install.sh, known only after download"]);
+        strings.insert("plan_remove", ["Будут удалены", "Packages to remove"]);
+        strings.insert("plan_not_installed", ["не установлен, pacman -R завершится ошибкой", "not installed, pacman -R will fail"]);
+        strings.insert("plan_delete", ["Будут удалены пути", "Paths to delete"]);
+        strings.insert("plan_path_missing", ["не существует, rm -r завершится ошибкой", "does not exist, rm -r will fail"]);
+        strings.insert("plan_backup", ["Архив настроек перед запуском", "Config backup before the run"]);
+        strings.insert("plan_os_release", ["Изменения os-release", "os-release changes"]);
+        strings.insert("plan_reboot", ["Перезагрузка", "Reboot"]);
+        strings.insert("plan_reboot_yes", ["Да", "Yes"]);
+        strings.insert("plan_reboot_no", ["Нет", "No"]);
+        strings.insert("plan_query_error", ["Запрос не выполнен, план неполон: {}", "Query failed, the plan is incomplete: {}"]);
+        strings.insert("plan_latest_release", ["<последний релиз>", "<latest release>"]);
+        strings.insert("plan_branch_head", ["<последний коммит {}>", "<latest {} commit>"]);
+
+        // Pacman lock recovery
+        strings.insert("lock_stale_confirm", [
+            "База pacman заблокирована (db.lck создан {} назад), но pacman, yay и paru не запущены. Удалить устаревшую блокировку?",
+            "The pacman database is locked (db.lck is {} old), but no pacman, yay or paru is running. Remove the stale lock?"]);
+        strings.insert("lock_remove_title", ["Удаление блокировки pacman", "Removing the pacman lock"]);
+        strings.insert("lock_remove_note", ["Удаление устаревшей блокировки {}, возраст {}", "Removing the stale lock {}, age {}"]);
+        strings.insert("lock_wait_title", ["Ожидание pacman", "Waiting for pacman"]);
+        strings.insert("lock_wait_message", [
+            "Ожидание завершения {} (PID {}): {}\n\nEsc - прекратить ожидание",
+            "Waiting for {} (PID {}) to finish: {}\n\nEsc - stop waiting"]);
+        strings.insert("lock_released", ["База pacman свободна, действие можно повторить", "The pacman database is free, the action can be run again"]);
+        strings.insert("lock_not_stale", ["Блокировка pacman уже снята или занята запущенным процессом", "The pacman lock is already gone or held by a running process"]);
+
+        // Channel switch
+        strings.insert("switch_channel_title", ["Смена канала системы", "Switch System Channel"]);
+        strings.insert("switch_channel_current", ["Текущий канал: {}, версия {}", "Current channel: {}, version {}"]);
+        strings.insert("switch_channel_targets", ["Доступные каналы", "Available channels"]);
+        strings.insert("switch_channel_resolving", ["Определение последней версии канала...", "Resolving the latest version of the channel..."]);
+        strings.insert("switch_channel_version_error", ["Не удалось определить версию канала: {}", "Failed to resolve the channel version: {}"]);
+        strings.insert("switch_channel_warnings", ["Предупреждения", "Warnings"]);
+        strings.insert("switch_channel_same", ["Система уже использует канал {}", "The system already uses the {} channel"]);
 
         // Script progress
         strings.insert("script_progress_title", ["Установка пакетов", "Package Installation"]);
+        strings.insert("install_progress_title", ["Установка системы", "System Installation"]);
+        strings.insert("update_progress_title", ["Обновление системы", "System Update"]);
+        strings.insert("switch_channel_progress_title", ["Смена канала", "Channel Switch"]);
+        strings.insert("uninstall_progress_title", ["Очистка системы", "System Uninstall"]);
         strings.insert("script_output", ["Вывод", "Output"]);
+        strings.insert("script_running", ["Выполняется...", "Running..."]);
+        strings.insert("script_steps", ["Этапы", "Steps"]);
+        strings.insert("script_steps_progress", ["Прогресс", "Progress"]);
+        strings.insert("input_title", ["Запрос ввода", "Input Request"]);
+        strings.insert("input_hints", ["Enter: Отправить | Esc: Скрыть", "Enter: Send | Esc: Hide"]);
+
+        // History
+        strings.insert("history_title", ["История запусков", "Run History"]);
+        strings.insert("history_runs", ["Запуски", "Runs"]);
+        strings.insert("history_read_error", ["Не удалось прочитать журнал: {}", "Failed to read log: {}"]);
+
+        // Backups
+        strings.insert("backup_created", ["Настройки сохранены в {}", "Configs saved to {}"]);
+        strings.insert("backup_failed", [
+            "Архив настроек не создан ({}), запуск продолжен без него",
+            "Config backup was not created ({}), the run continued without it"]);
+        strings.insert("backup_failed_continue", [
+            "Не удалось сохранить настройки: {}. Продолжить без архива? Настройки будут удалены безвозвратно",
+            "Failed to back up configs: {}. Continue without a backup? Configs will be deleted permanently"]);
+        strings.insert("backup_read_error", ["Не удалось прочитать архив: {}", "Failed to read the archive: {}"]);
+        strings.insert("backup_delete_error", ["Не удалось удалить архив: {}", "Failed to delete the archive: {}"]);
+        strings.insert("restore_title", ["Восстановление настроек", "Restore Configs"]);
+        strings.insert("restore_backups", ["Архивы", "Backups"]);
+        strings.insert("restore_paths", ["Пути для восстановления", "Paths to restore"]);
+        strings.insert("restore_overwrite", ["будет перезаписан", "will be overwritten"]);
+        strings.insert("restore_missing", ["сейчас отсутствует", "currently missing"]);
+        strings.insert("restore_nothing_selected", ["Не выбрано ни одного пути", "No paths selected"]);
+        strings.insert("restore_progress_title", ["Восстановление настроек", "Restoring configs"]);
+        strings.insert("warning_restore", [
+            "Восстановить отмеченные пути из {}? Текущие файлы будут перезаписаны",
+            "Restore the selected paths from {}? Current files will be overwritten"]);
+        strings.insert("warning_delete_backup", ["Удалить архив {}?", "Delete the archive {}?"]);
+
+        // Update rollback
+        strings.insert("snapshot_failed_continue", [
+            "Не удалось сохранить снимок системы: {}. Обновить без автоматического отката?",
+            "Failed to take a system snapshot: {}. Update without automatic rollback?"]);
+        strings.insert("rollback_progress_title", ["Откат обновления", "Rolling back the update"]);
+        strings.insert("rollback_started", ["Обновление не выполнено ({}), система возвращается к снимку", "The update failed ({}), returning the system to the snapshot"]);
+        strings.insert("rollback_not_needed", [
+            "Обновление не выполнено ({}). Система не была изменена, откат не нужен",
+            "The update failed ({}). The system was not changed, nothing to roll back"]);
+        strings.insert("rollback_done", [
+            "Обновление не выполнено ({}). Изменения отменены: {}",
+            "The update failed ({}). Rolled back: {}"]);
+        strings.insert("rollback_skipped", [
+            "Откат не выполнен, пока работают процессы обновления. Снимок сохранён в {}",
+            "Rollback was not started while update processes are still running. The snapshot is kept in {}"]);
+        strings.insert("rollback_failed", [
+            "Обновление не выполнено ({}), откат тоже не удался: {}. Снимок сохранён в {}",
+            "The update failed ({}) and so did the rollback: {}. The snapshot is kept in {}"]);
+        strings.insert("rollback_reinstalled", ["переустановлена версия {} {}", "reinstalled {} {}"]);
+        strings.insert("rollback_packages", ["возвращены пакеты: {}", "reinstalled packages: {}"]);
+        strings.insert("rollback_configs", ["восстановлены настройки", "restored configs"]);
+        strings.insert("rollback_os_release", ["восстановлен os-release", "restored os-release"]);
 
         // Instructions
         strings.insert("instructions_custom_package_input", [
@@ -96,6 +296,7 @@ impl Localization {
             "Are you sure you want to uninstall the entire system?\n\
             All data will be permanently deleted!"]);
         strings.insert("confirm_uninstall", ["Подтвердите удаление", "Confirm uninstall"]);
+        strings.insert("confirm_prompt", ["Продолжить? (y/n)", "Continue? (y/n)"]);
         strings.insert("warning_update_found", [
             "Найдена новая версия {}!\n\
             Вы действительно хотите обновить систему?", 
@@ -104,12 +305,45 @@ impl Localization {
         strings.insert("version_up_to_date", [
             "Версия системы актуальна", 
             "System version is up to date"]);
+        strings.insert("warning_version_upgrade", [
+            "Обновить систему до версии {}?",
+            "Update the system to version {}?"]);
+        strings.insert("warning_version_downgrade", [
+            "Версия {} старше установленной.\n\
+            Вы действительно хотите откатить систему?",
+            "Version {} is older than the installed one.\n\
+            Are you sure you want to roll the system back?"]);
+        strings.insert("warning_version_reinstall", [
+            "Версия {} уже установлена. Переустановить её?",
+            "Version {} is already installed. Reinstall it?"]);
+        strings.insert("warning_switch_channel", [
+            "Смена канала: {} -> {}\n\
+            Целевая версия: {}",
+            "Channel switch: {} -> {}\n\
+            Target version: {}"]);
+        strings.insert("warning_switch_channel_reinstall", [
+            "Текущая сборка будет удалена и установлена заново из нового канала.",
+            "The current build will be removed and installed again from the new channel."]);
+        strings.insert("warning_switch_channel_downgrade", [
+            "Новый канал стабильнее текущего: изменения, которых в нём ещё нет, будут отменены, \
+            а настройки, созданные более новой сборкой, могут оказаться несовместимы.",
+            "The new channel is more stable than the current one: changes it does not have yet will be rolled back, \
+            and settings created by the newer build may be incompatible."]);
+        strings.insert("version_ahead", [
+            "Установленная версия {} новее последней доступной {}",
+            "Installed version {} is newer than the latest available {}"]);
+        strings.insert("downgrade_refused", [
+            "Версия {} старше установленной {}. Для понижения версии используйте --allow-downgrade",
+            "Version {} is older than the installed {}. Use --allow-downgrade to downgrade"]);
         
         // Error handling
         strings.insert("command_success", ["Программа завершилась успешно", "Command completed successfully"]);
         strings.insert("command_error", ["Программа завершилась с ошибкой", "Command failed"]);
         strings.insert("command_error_start", ["Ошибка запуска: {}", "Command failed: {}"]);
+        strings.insert("config_error", ["Ошибка в файле настроек {}", "Invalid config file {}"]);
         strings.insert("command_error_process", ["Ошибка выполнения: {}", "Command failed: {}"]);
+        strings.insert("command_exited", ["{} завершилась с кодом {} через {}", "{} exited {} after {}"]);
+        strings.insert("command_killed", ["{} прервана сигналом {} через {}", "{} killed by signal {} after {}"]);
         strings.insert("system_already_installed", ["Система уже установлена", "System already installed"]);
         strings.insert("system_not_detected", ["Не удалось определить операционную систему", "System not detected"]);
         strings.insert("update_not_supported", ["Обновление не поддерживается для данной операционной системы: {}", "Update not supported for this operating system: {}"]);
@@ -129,20 +363,35 @@ impl Localization {
             "Enter: Установить | Esc: Назад", 
             "Enter: Install | Esc: Back"]);
         strings.insert("script_running_hints", [
-            "Выполняется программа... | Esc: Отмена", 
-            "Script is running... | Esc: Cancel"]);
+            "Выполняется программа... | ↑/↓/PgUp/PgDn: Прокрутка | End: Следить за выводом | l: Журнал | i: Ввод | Esc: Отмена", 
+            "Script is running... | ↑/↓/PgUp/PgDn: Scroll | End: Follow output | l: Log | i: Input | Esc: Cancel"]);
         strings.insert("script_finished_hints", [
-            "Программа завершена | Enter: Закрыть | Esc: Вернуться", 
-            "Script finished | Enter: Close | Esc: Back"]);
+            "Программа завершена | ↑/↓/PgUp/PgDn: Прокрутка | l: Журнал | Enter: Закрыть | Esc: Вернуться", 
+            "Script finished | ↑/↓/PgUp/PgDn: Scroll | l: Log | Enter: Close | Esc: Back"]);
         strings.insert("installation_type_navigation_hints", [
-            "↑/↓: Навигация | Enter: Выбрать | q: Выход", 
-            "↑/↓: Navigation | Enter: Select | q: Exit"]);
+            "↑/↓: Навигация | Enter: Выбрать | p: Предпросмотр | q: Выход", 
+            "↑/↓: Navigation | Enter: Select | p: Preview | q: Exit"]);
         strings.insert("update_check_hints", [
             "Проверка обновлений... | Esc: Отмена", 
             "Update check... | Esc: Cancel"]);
+        strings.insert("history_navigation_hints", [
+            "↑/↓: Навигация | Enter: Открыть журнал | Esc: Назад | q: Выход", 
+            "↑/↓: Navigation | Enter: Open log | Esc: Back | q: Exit"]);
+        strings.insert("restore_navigation_hints", [
+            "↑/↓: Навигация | Enter: Открыть архив | d: Удалить | Esc: Назад | q: Выход",
+            "↑/↓: Navigation | Enter: Open archive | d: Delete | Esc: Back | q: Exit"]);
+        strings.insert("restore_entries_navigation_hints", [
+            "↑/↓: Навигация | Space: Отметить | Enter: Восстановить | Esc: Назад | q: Выход",
+            "↑/↓: Navigation | Space: Toggle | Enter: Restore | Esc: Back | q: Exit"]);
+        strings.insert("versions_navigation_hints", [
+            "↑/↓: Навигация | Enter: Установить версию | Esc: Назад | q: Выход",
+            "↑/↓: Navigation | Enter: Install version | Esc: Back | q: Exit"]);
+        strings.insert("switch_channel_navigation_hints", [
+            "↑/↓: Навигация | Enter: Выбрать | Esc: Назад | q: Выход",
+            "↑/↓: Navigation | Enter: Select | Esc: Back | q: Exit"]);
         strings.insert("uninstall_type_navigation_hints", [
-            "↑/↓: Навигация | Enter: Выбрать | q: Выход", 
-            "↑/↓: Navigation | Enter: Select | q: Exit"]);
+            "↑/↓: Навигация | Enter: Выбрать | p: Предпросмотр | q: Выход", 
+            "↑/↓: Navigation | Enter: Select | p: Preview | q: Exit"]);
         strings.insert("uninstall_confirmation", [
             "{}\n\nEnter - Подтвердить\nEsc - Отменить", 
             "{}\n\nEnter - Confirm\nEsc - Cancel"]);
@@ -159,6 +408,15 @@ impl Localization {
         strings.insert("error", ["Ошибка", "Error"]);
         strings.insert("info", ["Информация", "Info"]);
         strings.insert("task_cancelled", ["Задача отменена", "Task Cancelled"]);
+        strings.insert("task_cancelling", ["Завершение задачи...", "Cancelling task..."]);
+        strings.insert("task_cancel_incomplete", ["Задача отменена не полностью", "Task Partially Cancelled"]);
+        strings.insert("task_cancel_survivors", [
+            "Не удалось завершить процессы: {}. Без сохранённых прав sudo сигнал получил только sudo; завершите их вручную (sudo kill)",
+            "Failed to terminate processes: {}. Without cached sudo credentials only sudo received the signal; stop them manually (sudo kill)"]);
+        strings.insert("cancel_package_transaction", [
+            "Выполняется транзакция pacman. Прерывание может повредить базу пакетов и оставить /var/lib/pacman/db.lck. Всё равно отменить?",
+            "A pacman transaction is in progress. Cancelling may corrupt the package database and leave /var/lib/pacman/db.lck behind. Cancel anyway?",
+        ]);
 
         Self { strings }
     }
@@ -172,6 +430,24 @@ impl Localization {
     pub fn get_fmt(&self, key: &str, lang: Language, arg: &str) -> String {
         self.get(key, lang).replace("{}", arg)
     }
+
+    // Подставляет аргументы по порядку вместо каждого {}
+    pub fn get_fmt_args(&self, key: &str, lang: Language, args: &[&str]) -> String {
+        let template = self.get(key, lang);
+        let mut parts = template.split("{}");
+        let mut result = parts.next().unwrap_or_default().to_string();
+        for (i, part) in parts.enumerate() {
+            result.push_str(args.get(i).copied().unwrap_or("{}"));
+            result.push_str(part);
+        }
+        result
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 lazy_static::lazy_static! {
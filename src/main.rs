@@ -1,15 +1,43 @@
+pub mod ansi;
+pub mod backup;
+pub mod channel;
+pub mod check_cache;
+pub mod config;
+pub mod history;
 pub mod localization;
-
+pub mod markdown;
+pub mod os_release;
+pub mod packages;
+pub mod plan;
+pub mod preflight;
+pub mod process;
+pub mod pty;
+pub mod report;
+pub mod steps;
+pub mod summary;
+#[cfg(test)]
+mod test_support;
+pub mod timer;
+pub mod transaction;
+pub mod uninstall;
+pub mod update_check;
+pub mod version;
+pub mod waybar;
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::ffi::OsStr;
-use std::io::{self, BufReader, Result};
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::os::unix::process::ExitStatusExt;
+use std::io::{self, IsTerminal, Result};
+use std::process::{Command, ExitStatus, Stdio};
 use std::fs::File;
-use std::io::BufRead;
-use std::sync::mpsc::{channel, Receiver};
+use std::io::{Read, Write};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use crossterm::{
     event::{self, Event, KeyCode},
     execute,
@@ -17,13 +45,35 @@ use crossterm::{
 };
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, Paragraph, ListState, Wrap, Clear, Scrollbar, ScrollbarState, ScrollbarOrientation},
+    widgets::{Block, Borders, List, ListItem, Paragraph, ListState, Wrap, Clear, Scrollbar, ScrollbarState, ScrollbarOrientation, Gauge},
 };
-use sysinfo::{Pid, System};
+use crate::ansi::AnsiLineBuffer;
+use crate::backup::Backup;
+use crate::channel::Channel;
+use crate::check_cache::{CheckReport, CheckStatus};
+use crate::config::{Config, SCRIPTS_DIR_ENV};
+use crate::history::{RunLog, RunRecord};
 use crate::localization::{Language, L10N};
-
-const OS_NAME: &str = "Kite Linux";
-const DEFAULT_LANGUAGE: Language = Language::Russian;
+use crate::os_release::OsRelease;
+use crate::plan::Plan;
+use crate::preflight::{CheckLevel, Operation, PacmanLock, PreflightCheck};
+use crate::process::{package_transaction_running, terminate_tree};
+use crate::report::{PackagesReport, StatusReport};
+use crate::steps::{StepState, StepTracker};
+use crate::summary::{check_status_text, format_duration, short_version, SystemSummary, UpdateCheckRecord};
+use crate::transaction::Snapshot;
+use crate::uninstall::UninstallType;
+use crate::update_check::{Changelog, UpdateCheckResult, VersionEntry};
+use crate::version::{compare_releases, same_commit, version_change, VersionChange};
+use crate::waybar::WaybarModule;
+
+// Время тишины в выводе, после которого незавершённая строка считается запросом ввода
+const PROMPT_IDLE_TIMEOUT: Duration = Duration::from_millis(300);
+// Количество последних строк вывода, сохраняемых для сообщения об ошибке
+const OUTPUT_TAIL_LINES: usize = 5;
+
+// Как часто проверять, освободил ли пакетный менеджер базу pacman
+const LOCK_WAIT_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Parser)]
 #[command(name = "kite-tools")]
@@ -31,48 +81,203 @@ const DEFAULT_LANGUAGE: Language = Language::Russian;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Do not ask for confirmation
+    #[arg(short, long, global = true)]
+    yes: bool,
+
+    /// Do not reboot the system after the script finishes
+    #[arg(long, global = true)]
+    no_reboot: bool,
+
+    /// Show what would be done without changing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Directory with the system scripts
+    #[arg(long, global = true, value_name = "DIR", env = SCRIPTS_DIR_ENV)]
+    scripts_dir: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum TimerAction {
+    /// Write and enable the timer and service units
+    Install {
+        /// Interval between checks in systemd time format
+        #[arg(long, default_value = timer::DEFAULT_INTERVAL)]
+        interval: String,
+    },
+    /// Disable and delete the units
+    Remove,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    // Install the system
-    Install,
-    // Update the system
-    Update,
-    // Uninstall the system
-    Uninstall,
-    // Install additional packages
-    InstallPackage,
+    /// Install the system
+    Install {
+        /// Installation type
+        #[arg(value_enum)]
+        r#type: Channel,
+    },
+    /// Update the system
+    Update {
+        /// Update to the specified version
+        #[arg(short, long)]
+        version: Option<String>,
+
+        /// Skip the update check and reinstall the current version
+        #[arg(long, conflicts_with = "version")]
+        skip_check: bool,
+
+        /// Allow updating to a release older than the installed one
+        #[arg(long)]
+        allow_downgrade: bool,
+    },
+    /// Uninstall the system
+    Uninstall {
+        /// Uninstall type
+        #[arg(value_enum)]
+        r#type: UninstallType,
+    },
+    /// Switch the installed system to another channel
+    SwitchChannel {
+        /// Target channel
+        #[arg(value_enum)]
+        r#type: Channel,
+    },
+    /// Show the system status
+    Status {
+        /// Print the status as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check for updates without installing them
+    Check {
+        /// Print nothing except errors
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Show a desktop notification when an update is available
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Manage the systemd user timer for background update checks
+    Timer {
+        #[command(subcommand)]
+        action: TimerAction,
+    },
+    /// Print the system status for a waybar custom module
+    Waybar {
+        /// Open the update check in the interface instead of printing the status
+        #[arg(long)]
+        on_click: bool,
+    },
+    /// List packages from the package list with their install status
+    Packages {
+        /// Print the list as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Install additional packages
+    InstallPackage {
+        /// Package names
+        #[arg(required = true)]
+        packages: Vec<String>,
+    },
 }
 
+type ConfirmationFn = Box<dyn FnOnce(&mut App)>;
+// Действие после успешных предварительных проверок; получает их таблицу
+type PreflightFn = Box<dyn FnOnce(&mut App, Vec<Line<'static>>)>;
+
 struct App {
+    config: Config,
+    system_summary: SystemSummary,
+    last_update_check: Option<UpdateCheckRecord>,
+    update_receiver: Option<Receiver<(UpdateCheckResult, Option<Changelog>)>>,
+    versions: Vec<VersionEntry>,
+    versions_state: ListState,
+    versions_channel: Option<Channel>,
+    versions_receiver: Option<Receiver<std::result::Result<Vec<VersionEntry>, String>>>,
+    channel_switch_state: ListState,
+    channel_switch_targets: Vec<Channel>,
+    channel_switch_current: Option<Channel>,
+    switch_receiver: Option<Receiver<(Channel, std::result::Result<String, String>)>>,
+    // Идущие предварительные проверки и действие, которое ждёт их результата
+    preflight_receiver: Option<Receiver<(Vec<PreflightCheck>, PacmanLock)>>,
+    preflight_next: Option<PreflightFn>,
+    // Пакетный менеджер, завершения которого ждём: имя, PID и начало ожидания
+    lock_wait: Option<(String, u32, Instant)>,
+    lock_wait_checked: Instant,
     menu_state: ListState,
     menu_items: Vec<&'static str>,
     status: String,
     error: Option<String>,
     show_error: bool,
     confirmation: Option<String>,
-    confirmation_fn: Option<Box<dyn FnOnce(&mut Self)>>,
+    confirmation_fn: Option<ConfirmationFn>,
+    // Прокручиваемый текст под подтверждением, например список изменений
+    confirmation_details: Option<Vec<Line<'static>>>,
+    confirmation_details_title: &'static str,
+    confirmation_scroll: usize,
     show_confirmation: bool,
     package_list: Vec<String>,
     package_state: ListState,
     custom_package_input: String,
     view_state: ViewState,
     selected_packages: Vec<bool>,
-    script_output: Vec<String>,
+    script_output: Vec<Line<'static>>,
+    script_partial_line: Option<Line<'static>>,
     script_receiver: Option<Receiver<CommandState>>,
     script_last_view_state: ViewState,
     script_process: Option<u32>,
+    // Результат завершения отменённой задачи: пережившие его процессы
+    terminate_receiver: Option<Receiver<Vec<(String, u32)>>>,
+    script_title: &'static str,
+    script_result: Option<ScriptResult>,
+    script_follow: bool,
+    script_steps: StepTracker,
+    script_show_log: bool,
+    script_input: Option<File>,
+    script_last_output: Instant,
+    script_prompt_answered: bool,
+    // Строка для журнала и вывода следующего запуска, например возраст удаляемой блокировки
+    script_note: Option<String>,
+    // Снимок идущего обновления и идущий откат
+    transaction: Option<Snapshot>,
+    rollback: Option<RollbackRun>,
+    show_input: bool,
+    input_prompt: String,
+    input_value: String,
+    input_masked: bool,
     installation_type_state: ListState,
-    installation_types: Vec<(&'static str, &'static str, &'static str)>,
+    installation_types: Vec<(&'static str, Channel, &'static str)>,
     uninstall_type_state: ListState,
-    uninstall_types: Vec<(&'static str, &'static str, &'static str)>,
+    uninstall_types: Vec<(&'static str, UninstallType, &'static str)>,
+    history_state: ListState,
+    history: Vec<RunRecord>,
+    backups_state: ListState,
+    backups: Vec<Backup>,
+    // Пути открытого архива и отметки восстановления
+    backup_entries_state: ListState,
+    backup_entries: Vec<(PathBuf, bool)>,
     new_version: Option<String>,
+    allow_downgrade: bool,
     scroll_position: usize,
     info_message: Option<String>,
     show_info: bool,
     terminal_clear: bool,
     language: Language,
+    headless: bool,
+    assume_yes: bool,
+    no_reboot: bool,
+    // Вместо запуска скриптов показывается план
+    dry_run: bool,
+    exit_code: Option<i32>,
 }
 
 #[derive(Clone, Copy)]
@@ -80,45 +285,162 @@ enum ViewState {
     MainMenu,
     PackageList,
     CustomPackageInput,
-    _ScriptProgress,
+    ScriptProgress,
     InstallationType,
     UpdateCheck,
     UninstallType,
+    History,
+    Versions,
+    ChannelSwitch,
+    Backups,
+    BackupEntries,
 }
 
 enum CommandState {
-    OutputLine(String),
+    OutputLine(Line<'static>),
+    PartialLine(Option<Line<'static>>),
     Completed,
-    Exit,
+    Exit(CommandOutcome),
     StartError(std::io::Error),
     WaitError(std::io::Error),
 }
 
+// Итог выполнения программы
+struct CommandOutcome {
+    name: String,
+    code: Option<i32>,
+    signal: Option<i32>,
+    duration: Duration,
+    output_tail: Vec<String>,
+}
+
+impl CommandOutcome {
+    fn new(name: String, status: ExitStatus, duration: Duration, output_tail: Vec<String>) -> Self {
+        Self {
+            name,
+            code: status.code(),
+            signal: status.signal(),
+            duration,
+            output_tail,
+        }
+    }
+
+    // Код завершения как в командной оболочке: 128 + номер сигнала для убитых процессов
+    fn exit_code(&self) -> i32 {
+        match (self.code, self.signal) {
+            (Some(code), _) => code,
+            (None, Some(signal)) => 128 + signal,
+            (None, None) => 1,
+        }
+    }
+
+    // Строка с ошибкой из вывода: предпочтительно `Error: ...`, иначе последняя строка
+    fn error_line(&self) -> Option<&str> {
+        self.output_tail.iter()
+            .rev()
+            .find(|line| line.trim_start().starts_with("Error:"))
+            .or(self.output_tail.last())
+            .map(|line| line.trim())
+    }
+
+    // update.sh exited 1 after 42s: Error: Failed to update packages
+    fn describe(&self, language: Language) -> String {
+        let duration = format_duration(self.duration);
+        let mut message = match (self.code, self.signal) {
+            (None, Some(signal)) => L10N.get_fmt_args("command_killed", language, &[&self.name, &signal.to_string(), &duration]),
+            _ => L10N.get_fmt_args("command_exited", language, &[&self.name, &self.exit_code().to_string(), &duration]),
+        };
+
+        if let Some(error) = self.error_line() {
+            message.push_str(": ");
+            message.push_str(error);
+        }
+        message
+    }
+}
+
+// Последние строки вывода программы
+#[derive(Default)]
+struct OutputTail {
+    lines: VecDeque<String>,
+    parser: AnsiLineBuffer,
+    pending: Vec<u8>,
+}
+
+impl OutputTail {
+    fn push_line(&mut self, line: String) {
+        if line.trim().is_empty() {
+            return;
+        }
+        if self.lines.len() == OUTPUT_TAIL_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    // Сырой вывод разбирается на строки без управляющих последовательностей
+    fn push_bytes(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        let text = take_utf8(&mut self.pending);
+        for line in self.parser.feed(&text) {
+            self.push_line(line.to_string());
+        }
+    }
+
+    fn into_lines(mut self) -> Vec<String> {
+        if let Some(line) = self.parser.finish() {
+            self.push_line(line.to_string());
+        }
+        self.lines.into()
+    }
+}
+
+enum ScriptResult {
+    Success,
+    Failure(String),
+}
+
+// Откат неудачного обновления: этап, на котором оно прервалось, и что возвращается
+struct RollbackRun {
+    failure: String,
+    actions: Vec<String>,
+    snapshot: Snapshot,
+    exit_code: Option<i32>,
+}
+
 impl App {
-    fn new() -> Self {
+    fn new(config: Config) -> Self {
         let menu_items = vec![
             "menu_install",
             "menu_update",
+            "menu_versions",
+            "menu_switch_channel",
             "menu_uninstall",
             "menu_install_package",
+            "menu_restore",
+            "menu_history",
         ];
         let mut state = ListState::default();
         state.select(Some(0));
+
+        let system_summary = SystemSummary::collect(&config.system_root);
+        let last_update_check = check_cache::load(system_summary.channel.as_deref(), system_summary.version.as_deref())
+            .map(|report| report.record());
         
         let installation_types = vec![
             (
                 "installation_types_stable",
-                "stable",
+                Channel::Stable,
                 "installation_types_description_stable"
             ),
             (
                 "installation_types_developer",
-                "developer",
+                Channel::Developer,
                 "installation_types_description_developer"
             ),
             (
                 "installation_types_experimental",
-                "experimental",
+                Channel::Experimental,
                 "installation_types_description_experimental"
             ),
         ];
@@ -126,17 +448,17 @@ impl App {
         let uninstall_types = vec![
             (
                 "uninstall_types_config",
-                "config",
+                UninstallType::Config,
                 "uninstall_types_description_config"
             ),
             (
                 "uninstall_types_apps",
-                "apps",
+                UninstallType::Apps,
                 "uninstall_types_description_apps"
             ),
             (
                 "uninstall_types_full",
-                "full",
+                UninstallType::Full,
                 "uninstall_types_description_full"
             ),
         ];
@@ -144,11 +466,14 @@ impl App {
         Self {
             menu_state: state,
             menu_items,
-            status: L10N.get("welcome_menu_status", DEFAULT_LANGUAGE),
+            status: L10N.get("welcome_menu_status", config.language),
             error: None,
             show_error: false,
             confirmation: None,
             confirmation_fn: None,
+            confirmation_details: None,
+            confirmation_details_title: "changelog_title",
+            confirmation_scroll: 0,
             show_confirmation: false,
             package_list: Vec::new(),
             package_state: ListState::default(),
@@ -156,19 +481,64 @@ impl App {
             view_state: ViewState::MainMenu,
             selected_packages: Vec::new(),
             script_output: Vec::new(),
+            script_partial_line: None,
             script_receiver: None,
             script_last_view_state: ViewState::MainMenu,
             script_process: None,
+            terminate_receiver: None,
+            script_title: "script_progress_title",
+            script_result: None,
+            script_follow: true,
+            script_steps: StepTracker::default(),
+            script_show_log: true,
+            script_input: None,
+            script_last_output: Instant::now(),
+            script_prompt_answered: false,
+            script_note: None,
+            transaction: None,
+            rollback: None,
+            show_input: false,
+            input_prompt: String::new(),
+            input_value: String::new(),
+            input_masked: false,
             installation_type_state: ListState::default(),
             installation_types,
             uninstall_type_state: ListState::default(),
             uninstall_types,
+            history_state: ListState::default(),
+            history: Vec::new(),
+            backups_state: ListState::default(),
+            backups: Vec::new(),
+            backup_entries_state: ListState::default(),
+            backup_entries: Vec::new(),
             new_version: None,
+            allow_downgrade: false,
             scroll_position: 0,
             info_message: None,
             show_info: false,
             terminal_clear: false,
-            language: DEFAULT_LANGUAGE,
+            language: config.language,
+            headless: false,
+            assume_yes: false,
+            no_reboot: false,
+            dry_run: false,
+            exit_code: None,
+            system_summary,
+            last_update_check,
+            update_receiver: None,
+            versions: Vec::new(),
+            versions_state: ListState::default(),
+            versions_channel: None,
+            versions_receiver: None,
+            channel_switch_state: ListState::default(),
+            channel_switch_targets: Vec::new(),
+            channel_switch_current: None,
+            switch_receiver: None,
+            preflight_receiver: None,
+            preflight_next: None,
+            lock_wait: None,
+            lock_wait_checked: Instant::now(),
+            config,
         }
     }
 
@@ -201,8 +571,12 @@ impl App {
                     match self.menu_items[selected] {
                         "menu_install" => self.handle_install(),
                         "menu_update" => self.handle_update(),
+                        "menu_versions" => self.load_versions(),
+                        "menu_switch_channel" => self.handle_switch_channel(),
                         "menu_uninstall" => self.handle_uninstall(),
                         "menu_install_package" => self.load_packages(),
+                        "menu_restore" => self.load_backups(),
+                        "menu_history" => self.load_history(),
                         _ => {}
                     }
                 }
@@ -213,14 +587,12 @@ impl App {
             ViewState::CustomPackageInput => {
                 self.install_custom_packages();
             }
-            ViewState::_ScriptProgress => {
+            ViewState::ScriptProgress => {
                 self.update_script_progress();
             }
             ViewState::InstallationType => {
                 if !self.show_confirmation {
                     self.handle_installation_type();
-                } else {
-                    self.run_installation_script();
                 }
             }
             ViewState::UpdateCheck => {
@@ -232,66 +604,136 @@ impl App {
                     self.handle_uninstall_type();
                 }
             }
+            ViewState::History => {
+                self.open_history_record();
+            }
+            ViewState::Backups => {
+                self.open_backup();
+            }
+            ViewState::BackupEntries => {
+                self.confirm_restore();
+            }
+            ViewState::Versions => {
+                self.select_version();
+            }
+            ViewState::ChannelSwitch => {
+                let target = self.channel_switch_state.selected()
+                    .and_then(|selected| self.channel_switch_targets.get(selected))
+                    .copied();
+                if let Some(target) = target {
+                    self.select_switch_target(target);
+                }
+            }
         }
     }
     
-    fn run_command<I>(&mut self, program: I, args: Vec<String>) -> Receiver<CommandState>
+    fn run_command_progress<I>(&mut self, title: &'static str, steps: &[&str], program: I, args: Vec<String>)
+    where
+        I: AsRef<OsStr> + Send + 'static,
+    {
+        if self.headless {
+            self.run_command_headless(program, args);
+            return;
+        }
+
+        self.scroll_position = 0;
+        self.script_follow = true;
+        self.script_output.clear();
+        self.script_partial_line = None;
+        self.script_result = None;
+        self.script_title = title;
+        self.script_steps = StepTracker::new(steps);
+        self.script_show_log = self.script_steps.is_empty();
+        self.script_last_view_state = self.view_state;
+        self.set_view_state(ViewState::ScriptProgress);
+        self.script_receiver = Some(self.run_command_pty(program, args));
+    }
+
+    // Запуск в псевдотерминале: сохраняются цвета и строки прогресса pacman/git
+    fn run_command_pty<I>(&mut self, program: I, args: Vec<String>) -> Receiver<CommandState>
     where
         I: AsRef<OsStr> + Send + 'static,
     {
         let (tx, rx) = channel();
 
-        let process = Command::new(program)
-            .args(args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn();
+        let log = RunLog::create(&program.as_ref().to_string_lossy(), &args);
+        if let Some(note) = self.script_note.take() {
+            if let Some(log) = &log {
+                log.write(format!("{}\n", note).as_bytes());
+            }
+            self.script_output.push(Line::from(note).style(Style::default().fg(Color::Yellow)));
+        }
+        let name = command_name(program.as_ref(), &args);
+        let started = Instant::now();
+        let mut command = Command::new(program);
+        command.args(args);
 
-        match process {
-            Ok(mut child) => {
+        // Размер терминала соответствует окну вывода
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+
+        match pty::spawn(command, cols.saturating_sub(2), rows.saturating_sub(11)) {
+            Ok((mut child, master)) => {
                 self.script_process = Some(child.id());
+                self.script_input = master.try_clone().ok();
+                self.script_last_output = Instant::now();
+                self.script_prompt_answered = false;
+
+                let output_tx = tx.clone();
+                let output_log = log.clone();
+                let reader = thread::spawn(move || {
+                    let mut master = master;
+                    let mut parser = AnsiLineBuffer::new();
+                    let mut tail = OutputTail::default();
+                    let mut pending = Vec::new();
+                    let mut buffer = [0u8; 4096];
+
+                    // EIO означает, что все процессы закрыли терминал
+                    while let Ok(count) = master.read(&mut buffer) {
+                        if count == 0 {
+                            break;
+                        }
 
-                // Получаем stdout и stderr
-                if let Some(stdout) = child.stdout.take() {
-                    let tx = tx.clone();
-                    thread::spawn(move || {
-                        let reader = BufReader::new(stdout);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                tx.send(CommandState::OutputLine(line)).unwrap_or_default();
-                            }
+                        if let Some(log) = &output_log {
+                            log.write(&buffer[..count]);
                         }
-                    });
-                }
 
-                if let Some(stderr) = child.stderr.take() {
-                    let tx = tx.clone();
-                    thread::spawn(move || {
-                        let reader = BufReader::new(stderr);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                tx.send(CommandState::OutputLine(line)).unwrap_or_default();
-                            }
+                        pending.extend_from_slice(&buffer[..count]);
+                        let text = take_utf8(&mut pending);
+                        for line in parser.feed(&text) {
+                            tail.push_line(line.to_string());
+                            output_tx.send(CommandState::OutputLine(line)).unwrap_or_default();
                         }
-                    });
-                }
+                        output_tx.send(CommandState::PartialLine(parser.current())).unwrap_or_default();
+                    }
+
+                    if let Some(line) = parser.finish() {
+                        tail.push_line(line.to_string());
+                        output_tx.send(CommandState::OutputLine(line)).unwrap_or_default();
+                    }
+                    output_tx.send(CommandState::PartialLine(None)).unwrap_or_default();
+
+                    // В терминале stdout и stderr объединены
+                    tail.into_lines()
+                });
 
                 // Ждем завершения процесса
                 thread::spawn(move || {
-                    match child.wait() {
-                        Ok(status) => {
-                            match status.success() {
-                                true => tx.send(CommandState::Completed).unwrap_or_default(),
-                                false => tx.send(CommandState::Exit).unwrap_or_default(),
-                            }
-                        }
-                        Err(e) => {
-                            tx.send(CommandState::WaitError(e)).unwrap_or_default();
-                        }
+                    let status = child.wait();
+                    let output_tail = reader.join().unwrap_or_default();
+
+                    if let Some(log) = &log {
+                        let status = status.as_ref().ok();
+                        log.finish(status.and_then(|status| status.code()), status.and_then(|status| status.signal()));
                     }
+
+                    tx.send(command_state(name, started, status, output_tail)).unwrap_or_default();
                 });
             }
             Err(e) => {
+                if let Some(log) = &log {
+                    log.write(e.to_string().as_bytes());
+                    log.finish(None, None);
+                }
                 tx.send(CommandState::StartError(e)).unwrap_or_default();
             }
         }
@@ -299,69 +741,80 @@ impl App {
         rx
     }
 
-    fn run_command_progress<I>(&mut self, program: I, args: Vec<String>)
+    fn run_command_headless<I>(&mut self, program: I, args: Vec<String>)
     where
-        I: AsRef<OsStr> + Send + 'static,
+        I: AsRef<OsStr>,
     {
-        /*
-            WIP: концепт выноса выполнение команды в отдельный сегмент программы нестабилен
-            Разработка такого концепта будет продолжена в будующем
-        */
-        
-        // self.scroll_position = 0;
-        // self.script_output.clear();
-        // self.script_last_view_state = self.view_state;
-        // self.view_state = ViewState::ScriptProgress;
-        // self.script_receiver = Some(self.run_command(program, args));
-
-        // Очищаем экран
-        execute!(io::stdout(), crossterm::terminal::Clear(crossterm::terminal::ClearType::All))
-            .expect("Failed to clear the screen");
-
-        // Отключаем режим raw
-        disable_raw_mode().expect("Failed to set raw mode");
-        execute!(io::stdout(), LeaveAlternateScreen).expect("Failed to leave alternate screen");
-
-        // Выполнение программы
-        let output = Command::new(program)
+        let log = RunLog::create(&program.as_ref().to_string_lossy(), &args);
+        if let Some(note) = self.script_note.take() {
+            if let Some(log) = &log {
+                log.write(format!("{}\n", note).as_bytes());
+            }
+            println!("{}", note);
+        }
+        let name = command_name(program.as_ref(), &args);
+        let started = Instant::now();
+
+        // Вывод программы дублируется в терминал и в журнал
+        let result = Command::new(program)
             .args(args)
-            .stdout(Stdio::inherit())
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output();
-
-        match output {
-            Ok(output) => {
-                if let Some(code) = output.status.code() {
-                    match code {
-                        0 => self.set_info(L10N.get("command_success", self.language)),
-                        _ => self.set_error(L10N.get_fmt("command_error", self.language, format!("{code}: {}", String::from_utf8_lossy(&output.stderr)).as_str())),
-                    }
+            .spawn()
+            .and_then(|mut child| {
+                let stdout_reader = child.stdout.take()
+                    .map(|stdout| spawn_output_tee(stdout, io::stdout(), log.clone()));
+                let stderr_reader = child.stderr.take()
+                    .map(|stderr| spawn_output_tee(stderr, io::stderr(), log.clone()));
+
+                let status = child.wait()?;
+                if let Some(reader) = stdout_reader {
+                    reader.join().unwrap_or_default();
+                }
+                let stderr_tail = stderr_reader
+                    .and_then(|reader| reader.join().ok())
+                    .unwrap_or_default();
+
+                Ok(CommandOutcome::new(name, status, started.elapsed(), stderr_tail))
+            });
+
+        if let Some(log) = &log {
+            match &result {
+                Ok(outcome) => log.finish(outcome.code, outcome.signal),
+                Err(e) => {
+                    log.write(e.to_string().as_bytes());
+                    log.finish(None, None);
+                }
+            }
+        }
+
+        match result {
+            Ok(outcome) => {
+                self.exit_code = Some(outcome.exit_code());
+                match outcome.exit_code() {
+                    0 => self.set_info(L10N.get("command_success", self.language)),
+                    _ => self.set_error(outcome.describe(self.language)),
                 }
             }
             Err(e) => {
+                self.exit_code = Some(1);
                 self.set_error(L10N.get_fmt("command_error_start", self.language, e.to_string().as_str()));
             }
         }
-        
-        // Включаем режим raw
-        enable_raw_mode().expect("Failed to set raw mode");
-        execute!(io::stdout(), EnterAlternateScreen).expect("Failed to enter alternate screen");
-        self.terminal_clear = true;
+
+        let failure = match self.exit_code {
+            Some(0) => None,
+            _ => Some(self.error.clone().unwrap_or_default()),
+        };
+        self.complete_transaction(failure);
     }
 
     fn load_packages(&mut self) {
-        // let package_list_path = format!("{}/.local/share/bin/custom_apps.lst", home_path());
-        let package_list_path = "/usr/src/kite-tools/custom_apps.lst";
-        
         self.package_list.clear();
         self.package_list.push(L10N.get("custom_packages", self.language));
 
-        if let Ok(file) = File::open(&package_list_path) {
-            let packages: Vec<String> = std::io::BufReader::new(file)
-                .lines()
-                .filter_map(Result::ok)
-                .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
-                .collect();
+        if let Ok(packages) = packages::read_list(&self.config.package_list()) {
             self.package_list.extend(packages);
         }
 
@@ -380,6 +833,7 @@ impl App {
         }
     }
 
+    // Enter в списке пакетов и run_selected_action ведут сюда же
     fn install_selected_packages(&mut self) {
         let selected_packages: Vec<String> = self.package_list.iter()
             .zip(self.selected_packages.iter())
@@ -389,13 +843,11 @@ impl App {
             .collect();
 
         if selected_packages.is_empty() {
+            self.status = L10N.get("package_error_status", self.language);
             return;
         }
 
-        let mut args: Vec<String> = vec!["pacman".to_string(), "-S".to_string(), "--noconfirm".to_string()];
-        args.extend(selected_packages);
-
-        self.run_command_progress("sudo", args);
+        self.install_packages(selected_packages);
     }
 
     fn install_custom_packages(&mut self) {
@@ -409,65 +861,143 @@ impl App {
             return;
         }
 
-        let mut args: Vec<String> = vec!["pacman".to_string(), "-S".to_string(), "--noconfirm".to_string()];
-        args.extend(packages);
-
-        self.run_command_progress("sudo", args);
+        self.install_packages(packages);
         self.custom_package_input.clear();
     }
 
-    fn start_package_installation(&mut self) {
-        let selected_packages: Vec<String> = self.package_list.iter()
-            .zip(self.selected_packages.iter())
-            .skip(1)
-            .filter(|(_, &selected)| selected)
-            .map(|(package, _)| package.clone())
-            .collect();
+    fn install_packages(&mut self, packages: Vec<String>) {
+        let mut args: Vec<String> = vec!["pacman".to_string(), "-S".to_string(), "--noconfirm".to_string()];
+        args.extend(packages.iter().cloned());
 
-        if selected_packages.is_empty() {
-            self.status = L10N.get("package_error_status", self.language);
+        if self.dry_run {
+            let plan = plan::install_packages(&self.config, &packages, command_line("sudo", &args));
+            self.show_plan(plan);
             return;
         }
-
-        let mut args: Vec<String> = vec!["pacman".to_string(), "-S".to_string(), "--noconfirm".to_string()];
-        args.extend(selected_packages);
-
-        self.run_command_progress("sudo", args);
+        self.run_command_progress("script_progress_title", &[], "sudo", args);
     }
 
     fn update_script_progress(&mut self) {
         if let Some(ref rx) = self.script_receiver {
             let mut clear_process = false;
+            let mut script_result = None;
             
             while let Ok(state) = rx.try_recv() {
-                match state {
-                    CommandState::OutputLine(line) => self.script_output.push(line),
-                    CommandState::Completed => {
-                        clear_process = true;
+                let result = match state {
+                    CommandState::OutputLine(line) => {
+                        self.script_steps.process_line(&line.to_string());
+                        self.script_output.push(line);
+                        self.script_last_output = Instant::now();
+                        self.script_prompt_answered = false;
+                        continue;
                     }
-                    CommandState::Exit => {
-                        self.error = Some(L10N.get("command_error", self.language));
-                        self.show_error = true;
-                        clear_process = true;
+                    CommandState::PartialLine(line) => {
+                        if line != self.script_partial_line {
+                            self.script_last_output = Instant::now();
+                            self.script_prompt_answered = false;
+                        }
+                        self.script_partial_line = line;
+                        continue;
                     }
+                    CommandState::Completed => ScriptResult::Success,
+                    CommandState::Exit(outcome) => ScriptResult::Failure(outcome.describe(self.language)),
                     CommandState::StartError(e) => {
-                        self.error = Some(L10N.get_fmt("command_error_start", self.language, e.to_string().as_str()));
-                        self.show_error = true;
-                        clear_process = true;
+                        ScriptResult::Failure(L10N.get_fmt("command_error_start", self.language, e.to_string().as_str()))
                     }
                     CommandState::WaitError(e) => {
-                        self.error = Some(L10N.get_fmt("command_error_process", self.language, e.to_string().as_str()));
-                        self.show_error = true;
-                        clear_process = true;
+                        ScriptResult::Failure(L10N.get_fmt("command_error_process", self.language, e.to_string().as_str()))
                     }
-                }
+                };
+
+                clear_process = true;
+                script_result = Some(result);
             }
 
             if clear_process {
                 self.script_receiver = None;
                 self.script_process = None;
+                self.script_input = None;
+                self.hide_input();
+            } else {
+                self.detect_input_prompt();
+            }
+
+            if let Some(ScriptResult::Failure(error)) = &script_result {
+                self.set_error(error.clone());
+            }
+            if let Some(result) = &script_result {
+                self.script_steps.finish(matches!(result, ScriptResult::Success));
+            }
+            if let Some(result) = script_result {
+                let failure = match &result {
+                    ScriptResult::Success => None,
+                    ScriptResult::Failure(error) => Some(error.clone()),
+                };
+                self.script_result = Some(result);
+                self.refresh_summary();
+                self.complete_transaction(failure);
+            }
+        }
+    }
+
+    // Кэш проверки перечитывается вместе со сводкой: после обновления или смены канала он устаревает
+    fn refresh_summary(&mut self) {
+        self.system_summary = SystemSummary::collect(&self.config.system_root);
+        self.last_update_check = check_cache::load(self.system_summary.channel.as_deref(), self.system_summary.version.as_deref())
+            .map(|report| report.record());
+    }
+
+    // Программа ждёт ввода, если вывод затих на известном приглашении
+    // или терминал переведён в режим без эха / неканонический режим.
+    // Прогресс вида "Resolving deltas: 45% (45/100)" при задержке сети приглашением не считается
+    fn detect_input_prompt(&mut self) {
+        if self.show_input || self.script_prompt_answered || self.script_last_output.elapsed() < PROMPT_IDLE_TIMEOUT {
+            return;
+        }
+        let (Some(input), Some(line)) = (&self.script_input, &self.script_partial_line) else {
+            return;
+        };
+
+        // Режим терминала меняют и обычные программы (индикаторы прогресса, read -s),
+        // поэтому диалог открывается только по тексту приглашения, а режим лишь скрывает ввод
+        let prompt = line.to_string();
+        if pty::is_known_prompt(&prompt) {
+            let masked = pty::input_mode(input).is_ok_and(|mode| !mode.echo);
+            self.show_input_prompt(prompt.trim().to_string(), masked);
+        }
+    }
+
+    fn show_input_prompt(&mut self, prompt: String, masked: bool) {
+        self.input_prompt = prompt;
+        self.input_value.clear();
+        self.input_masked = masked;
+        self.show_input = true;
+    }
+
+    fn hide_input(&mut self) {
+        self.show_input = false;
+        self.input_value.clear();
+    }
+
+    fn send_input(&mut self, data: &[u8]) {
+        if let Some(input) = self.script_input.as_mut() {
+            if let Err(e) = input.write_all(data).and_then(|_| input.flush()) {
+                self.set_error(L10N.get_fmt("command_error_process", self.language, e.to_string().as_str()));
             }
         }
+        self.script_prompt_answered = true;
+        self.hide_input();
+    }
+
+    fn dismiss_input(&mut self) {
+        self.script_prompt_answered = true;
+        self.hide_input();
+    }
+
+    fn submit_input(&mut self) {
+        let mut line = std::mem::take(&mut self.input_value);
+        line.push('\n');
+        self.send_input(line.as_bytes());
     }
 
     fn set_confirmation<F>(&mut self, confirmation: String, confirmation_fn: F)
@@ -477,12 +1007,122 @@ impl App {
         self.confirmation = Some(confirmation);
         self.confirmation_fn = Some(Box::new(confirmation_fn));
         self.show_confirmation = true;
+
+        // Без интерфейса подтверждение запрашивается в терминале
+        if self.headless {
+            if !self.assume_yes {
+                for line in self.confirmation_details.iter().flatten() {
+                    println!("{}", line_text(line));
+                }
+            }
+            // При --dry-run подтверждение ведёт только к показу плана
+            if self.assume_yes || self.dry_run || prompt_confirmation(self.confirmation.as_deref().unwrap_or_default(), self.language) {
+                self.accept_confirmation();
+            } else {
+                self.hide_confirmation();
+                self.set_error(L10N.get("task_cancelled", self.language));
+            }
+        }
+    }
+
+    fn set_detailed_confirmation<F>(&mut self, confirmation: String, details_title: &'static str, details: Vec<Line<'static>>, confirmation_fn: F)
+    where
+        F: FnOnce(&mut Self) + 'static,
+    {
+        self.confirmation_details = Some(details);
+        self.confirmation_details_title = details_title;
+        self.confirmation_scroll = 0;
+        self.set_confirmation(confirmation, confirmation_fn);
+    }
+
+    fn accept_confirmation(&mut self) {
+        let confirmation_fn = self.confirmation_fn.take();
+        // Окно скрывается до вызова: действие может показать следующее подтверждение
+        self.hide_confirmation();
+        if let Some(confirmation_fn) = confirmation_fn {
+            confirmation_fn(self);
+        }
     }
 
     fn hide_confirmation(&mut self) {
         self.show_confirmation = false;
         self.confirmation_fn = None;
         self.confirmation = None;
+        self.confirmation_details = None;
+    }
+
+    // Отмена задачи; во время транзакции pacman требует подтверждения
+    fn request_cancel(&mut self, next_view: ViewState) {
+        match self.script_process {
+            Some(pid) if package_transaction_running(pid) => {
+                self.set_confirmation(L10N.get("cancel_package_transaction", self.language), move |app| {
+                    app.cancel_script();
+                    app.set_view_state(next_view);
+                });
+            }
+            _ => {
+                self.cancel_script();
+                self.set_view_state(next_view);
+            }
+        }
+    }
+
+    // Отмена обновления или отката оставляет окно вывода: в нём запускается откат и видно его завершение
+    fn request_cancel_script(&mut self, next_view: ViewState) {
+        match self.transaction.is_some() || self.rollback.is_some() {
+            true => self.request_cancel(ViewState::ScriptProgress),
+            false => self.request_cancel(next_view),
+        }
+    }
+
+    fn cancel_script(&mut self) {
+        if let Some(pid) = self.script_process.take() {
+            self.terminate_receiver = Some(terminate_tree(pid));
+            self.status = L10N.get("task_cancelling", self.language);
+            // Этап, на котором прервано обновление, попадает в сообщение об откате
+            self.script_steps.finish(false);
+        }
+        if self.update_receiver.take().is_some() {
+            self.status = L10N.get("task_cancelled", self.language);
+        }
+        self.script_receiver = None;
+        self.script_input = None;
+        self.hide_input();
+    }
+
+    // Итог завершения отменённой задачи; процессы root без прав sudo могут пережить отмену
+    fn terminate_progress(&mut self) {
+        let Some(rx) = &self.terminate_receiver else {
+            return;
+        };
+        let survivors = match rx.try_recv() {
+            Ok(survivors) => survivors,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.terminate_receiver = None;
+        // Прерванная задача могла успеть изменить систему
+        self.refresh_summary();
+
+        if survivors.is_empty() {
+            self.status = L10N.get("task_cancelled", self.language);
+            // Прерванное обновление откатывается так же, как завершившееся с ошибкой
+            self.complete_transaction(Some(L10N.get("task_cancelled", self.language)));
+            return;
+        }
+        let survivors: Vec<String> = survivors.iter()
+            .map(|(name, pid)| format!("{} ({})", name, pid))
+            .collect();
+        self.status = L10N.get("task_cancel_incomplete", self.language);
+        let mut error = L10N.get_fmt("task_cancel_survivors", self.language, &survivors.join(", "));
+
+        // Откат поверх ещё работающего скрипта невозможен: снимок остаётся для ручного восстановления
+        let snapshot = self.transaction.take().or(self.rollback.take().map(|rollback| rollback.snapshot));
+        if let Some(snapshot) = snapshot {
+            error.push('\n');
+            error.push_str(&L10N.get_fmt("rollback_skipped", self.language, &snapshot.dir.display().to_string()));
+        }
+        self.set_error(error);
     }
 
     fn set_error(&mut self, error: String) {
@@ -505,6 +1145,15 @@ impl App {
         self.info_message = None;
     }
 
+    // os-release читается заново: состояние меняется после установки и удаления
+    fn os_release(&self) -> Option<OsRelease> {
+        OsRelease::load(&self.config.system_root).ok()
+    }
+
+    fn os_version(&self) -> Option<String> {
+        self.os_release().and_then(|os_release| os_release.version_id)
+    }
+
     fn set_view_state(&mut self, view_state: ViewState) {
         self.view_state = view_state;
         self.terminal_clear = true;
@@ -513,8 +1162,8 @@ impl App {
     }
 
     fn handle_install(&mut self) {
-        match get_os_name() {
-            Some(os_name) if os_name.contains(OS_NAME) => {
+        match self.os_release() {
+            Some(os_release) if os_release.is_kite() => {
                 self.set_error(L10N.get("system_already_installed", self.language));
             }
             Some(_) => {
@@ -531,20 +1180,21 @@ impl App {
         if let Some(selected) = self.installation_type_state.selected() {
             let install_type = L10N.get(self.installation_types[selected].0, self.language);   
             let confirmation = L10N.get_fmt("warning_installation", self.language, install_type.as_str());
-
-            self.set_confirmation(confirmation, move |this| {
-                this.run_selected_action();
+            self.preflight(Operation::Install, move |this, preflight| {
+                this.set_detailed_confirmation(confirmation, "preflight_title", preflight, move |this| {
+                    this.run_installation_script();
+                });
             });
         }
     }
 
     fn handle_update(&mut self) {
-        match get_os_name() {
-            Some(os_name) if os_name.contains(OS_NAME) => {
+        match self.os_release() {
+            Some(os_release) if os_release.is_kite() => {
                 self.check_updates();
             }
-            Some(os_name) => {
-                self.set_error(L10N.get_fmt("update_not_supported", self.language, os_name.as_str()));
+            Some(os_release) => {
+                self.set_error(L10N.get_fmt("update_not_supported", self.language, os_release.display_name()));
             }
             None => {
                 self.set_error(L10N.get("system_not_detected", self.language));
@@ -553,110 +1203,941 @@ impl App {
     }
 
     fn check_updates(&mut self) {
-        self.script_output.clear();
+        // Версия задана явно: проверка не нужна
+        if self.headless && self.new_version.is_some() {
+            self.preflight(Operation::Update, |this, preflight| {
+                if this.dry_run {
+                    for line in with_preflight(preflight, Vec::new(), this.language) {
+                        println!("{}", line_text(&line));
+                    }
+                }
+                this.start_update();
+            });
+            return;
+        }
+
+        let Some(os_release) = self.os_release() else {
+            self.set_error(L10N.get("system_not_detected", self.language));
+            return;
+        };
+        let build_id = os_release.build_id.unwrap_or_default();
+        let Some(system_channel) = Channel::from_build_id(&build_id) else {
+            self.set_error(L10N.get_fmt("unknown_channel", self.language, build_id.as_str()));
+            return;
+        };
+        let current_version = os_release.version_id;
+        let source = update_check::release_source(&self.config);
+
+        if self.headless {
+            let result = update_check::check(source.as_ref(), system_channel, current_version.as_deref());
+            // Список изменений нужен только для вопроса в терминале
+            let changelog = match &result {
+                UpdateCheckResult::Available { version } if !self.assume_yes => {
+                    update_check::changelog(source.as_ref(), system_channel, current_version.as_deref(), version).ok()
+                }
+                _ => None,
+            };
+            self.handle_update_check_result(result, changelog);
+            return;
+        }
+
+        self.script_output = vec![
+            Line::from(L10N.get_fmt("update_check_channel", self.language, &L10N.get(system_channel.title_key(), self.language))),
+            Line::from(L10N.get_fmt("update_check_current", self.language, current_version.as_deref().map_or("-", short_version))),
+            Line::from(L10N.get("update_check_running", self.language)),
+        ];
         self.script_last_view_state = self.view_state;
         self.set_view_state(ViewState::UpdateCheck);
-        
-        // let script_path = format!("{}/.local/share/bin/check_update.sh", home_path());
-        let script_path = "/usr/src/kite-tools/check_update.sh";
-        let rx = self.run_command(script_path, vec!["--no-info".to_string()]);
-        self.script_receiver = Some(rx);
+
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let result = update_check::check(source.as_ref(), system_channel, current_version.as_deref());
+            let changelog = match &result {
+                UpdateCheckResult::Available { version } => {
+                    update_check::changelog(source.as_ref(), system_channel, current_version.as_deref(), version).ok()
+                }
+                _ => None,
+            };
+            tx.send((result, changelog)).unwrap_or_default();
+        });
+        self.update_receiver = Some(rx);
     }
 
-    fn start_update(&mut self) {
-        // let script_path = format!("{}/.local/share/bin/update.sh", home_path());
-        let script_path = "/usr/src/kite-tools/update.sh".to_string();
-        let version = self.new_version.take().unwrap();
-        self.run_command_progress("sudo", vec![script_path, "--no-confirm".to_string(), "-v".to_string(), version]);
+    fn update_check_progress(&mut self) {
+        let Some(rx) = &self.update_receiver else {
+            return;
+        };
+        let (result, changelog) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => (UpdateCheckResult::Error(L10N.get("update_check_interrupted", self.language)), None),
+        };
+
+        self.update_receiver = None;
+        self.set_view_state(self.script_last_view_state);
+        self.handle_update_check_result(result, changelog);
     }
 
-    fn handle_uninstall(&mut self) {
-        match get_os_name() {
-            Some(os_name) if os_name.contains(OS_NAME) => {
-                self.set_view_state(ViewState::UninstallType);
-                self.uninstall_type_state.select(Some(0));
+    fn handle_update_check_result(&mut self, result: UpdateCheckResult, changelog: Option<Changelog>) {
+        match &result {
+            UpdateCheckResult::UpToDate if self.headless => {
+                self.set_info(L10N.get("version_up_to_date", self.language));
             }
-            Some(os_name) => {
-                self.set_error(L10N.get_fmt("update_not_supported", self.language, os_name.as_str()));
+            UpdateCheckResult::UpToDate => {
+                let confirmation = L10N.get("version_up_to_date", self.language);
+                self.set_confirmation(confirmation, move |this| {
+                    this.set_view_state(ViewState::MainMenu);
+                });
             }
-            None => {
-                self.set_error(L10N.get("system_not_detected", self.language));
+            UpdateCheckResult::Available { version } => {
+                let version = version.clone();
+                let confirmation = L10N.get_fmt("warning_update_found", self.language, version.as_str());
+                let changelog = build_changelog(changelog.as_ref(), self.language);
+                self.preflight(Operation::Update, move |this, preflight| {
+                    let details = with_preflight(preflight, changelog, this.language);
+                    this.set_detailed_confirmation(confirmation, "changelog_title", details, move |this| {
+                        this.set_view_state(ViewState::UpdateCheck);
+                        this.new_version = Some(version);
+                        this.run_selected_action();
+                    });
+                });
+            }
+            UpdateCheckResult::Ahead { version } => {
+                let current_version = self.os_version().unwrap_or_default();
+                self.set_info(L10N.get_fmt_args("version_ahead", self.language, &[
+                    short_version(&current_version),
+                    short_version(version),
+                ]));
+            }
+            UpdateCheckResult::Error(e) => {
+                self.set_error(L10N.get_fmt("update_check_error", self.language, e));
             }
         }
+
+        // Кэш общий с `kite-tools check`: главное меню показывает его сразу при запуске
+        let os_release = self.os_release();
+        let report = CheckReport::new(
+            &result,
+            os_release.as_ref().and_then(|os_release| os_release.build_id.as_deref()),
+            os_release.as_ref().and_then(|os_release| os_release.version_id.as_deref()),
+        );
+        check_cache::save(&report).unwrap_or_default();
+        self.last_update_check = Some(report.record());
     }
 
-    fn handle_uninstall_type(&mut self) {
-        if let Some(selected) = self.uninstall_type_state.selected() {
-            let uninstall_type = self.uninstall_types[selected].1;
-            let confirmation = match uninstall_type {
-                "config" => L10N.get("warning_uninstall_config", self.language),
-                "apps" => L10N.get("warning_uninstall_apps", self.language),
-                "full" => L10N.get("warning_uninstall_full", self.language),
-                _ => L10N.get("confirm_uninstall", self.language),
-            };
+    fn start_update(&mut self) {
+        // Понижение версии только по явному запросу; хеши коммитов локально не упорядочить
+        let allow_downgrade = std::mem::take(&mut self.allow_downgrade);
+        if let (Some(version), Some(current_version)) = (&self.new_version, self.os_version()) {
+            let downgrade = compare_releases(version, &current_version) == Some(Ordering::Less);
+            if downgrade && !allow_downgrade {
+                self.set_error(L10N.get_fmt_args("downgrade_refused", self.language, &[version, &current_version]));
+                self.new_version = None;
+                return;
+            }
+        }
 
-            // let script_path = format!("{}/.local/share/bin/uninstall.sh", home_path());
-            let script_path = "/usr/src/kite-tools/uninstall.sh".to_string();
-            let uninstall_arg = uninstall_type.to_string();
-            
-            self.set_confirmation(confirmation.to_string(), move |this| {
-                this.run_command_progress("sudo", vec![script_path, uninstall_arg, "--no-confirm".to_string()]);
-            });
+        let mut args = self.script_command("update.sh");
+        args.push("--no-confirm".to_string());
+        let version = self.new_version.take();
+        if let Some(version) = &version {
+            args.extend(["-v".to_string(), version.clone()]);
         }
-    }
+        args.extend(self.reboot_args());
 
-    fn run_installation_script(&mut self) {
-        if let Some(selected) = self.installation_type_state.selected() {
-            let itype = self.installation_types[selected].1;
-            // let script_path = format!("{}/.local/share/bin/install.sh", home_path());
-            let script_path = "/usr/src/kite-tools/install.sh".to_string();
-            self.run_command_progress("sudo", vec![script_path, itype.to_string(), "--no-confirm".to_string()]);
+        if self.dry_run {
+            let Some(channel) = self.installed_channel() else {
+                return;
+            };
+            let version = version.or_else(|| self.os_version()).unwrap_or_default();
+            let plan = plan::update(&self.config, channel, &version, &home_dir(), command_line("sudo", &args), !self.no_reboot);
+            self.show_plan(plan);
+            return;
         }
+        self.run_update("update_progress_title", args);
     }
-}
-
-// fn home_path() -> String {
-//     std::env::var("HOME").unwrap_or_else(|_| ".".to_string())
-// }
-
-// Функция для проверки OS
-fn get_os_name() -> Option<String> {
-    // if let Ok(output) = Command::new("cat")
-    //     .arg("/etc/os-release")
-    //     .output() {
-    //     let content = String::from_utf8_lossy(&output.stdout);
-    //     for line in content.lines() {
-    //         if line.starts_with("NAME=") {
-    //             return Some(line.trim_start_matches("NAME=")
-    //                 .trim_matches('"')
-    //                 .to_string());
-    //         }
-    //     }
-    // }
-    // None
-
-    System::name()
-}
-
-fn get_os_version() -> Option<String> {
-    // if let Ok(output) = Command::new("cat")
-    //     .arg("/etc/os-release")
-    //     .output() {
-    //     let content = String::from_utf8_lossy(&output.stdout);
-    //     for line in content.lines() {
-    //         if line.starts_with("VERSION=") {
-    //             return Some(line.trim_start_matches("VERSION=")
-    //                 .trim_matches('"')
-    //                 .to_string());
-    //         }
-    //     }
-    // }
-    // None
-
-    System::os_version()
-}
 
-fn run_tui() -> Result<()> {
+    // Канал установленной Kite; иначе показывает ошибку
+    fn installed_channel(&mut self) -> Option<Channel> {
+        let os_release = match self.os_release() {
+            Some(os_release) if os_release.is_kite() => os_release,
+            Some(os_release) => {
+                self.set_error(L10N.get_fmt("update_not_supported", self.language, os_release.display_name()));
+                return None;
+            }
+            None => {
+                self.set_error(L10N.get("system_not_detected", self.language));
+                return None;
+            }
+        };
+
+        let build_id = os_release.build_id.unwrap_or_default();
+        let channel = Channel::from_build_id(&build_id);
+        if channel.is_none() {
+            self.set_error(L10N.get_fmt("unknown_channel", self.language, build_id.as_str()));
+        }
+        channel
+    }
+
+    fn load_versions(&mut self) {
+        let Some(system_channel) = self.installed_channel() else {
+            return;
+        };
+
+        self.versions.clear();
+        self.versions_state.select(None);
+        self.versions_channel = Some(system_channel);
+        self.set_view_state(ViewState::Versions);
+
+        let source = update_check::release_source(&self.config);
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let result = update_check::list_versions(source.as_ref(), system_channel)
+                .map_err(|e| e.to_string());
+            tx.send(result).unwrap_or_default();
+        });
+        self.versions_receiver = Some(rx);
+    }
+
+    fn versions_progress(&mut self) {
+        let Some(rx) = &self.versions_receiver else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(L10N.get("update_check_interrupted", self.language)),
+        };
+
+        self.versions_receiver = None;
+        match result {
+            Ok(versions) => {
+                self.versions_state.select((!versions.is_empty()).then_some(0));
+                self.versions = versions;
+            }
+            Err(e) => {
+                self.set_view_state(ViewState::MainMenu);
+                self.set_error(L10N.get_fmt("versions_load_error", self.language, &e));
+            }
+        }
+    }
+
+    // Любую версию из списка можно установить: обновление, откат или переустановка
+    fn select_version(&mut self) {
+        let Some(selected) = self.versions_state.selected().filter(|&selected| selected < self.versions.len()) else {
+            return;
+        };
+
+        let current_version = self.os_version();
+        let versions: Vec<&str> = self.versions.iter().map(|entry| entry.version.as_str()).collect();
+        let change = version_change(&versions, selected, current_version.as_deref());
+        let version = self.versions[selected].version.clone();
+
+        let key = match change {
+            VersionChange::Upgrade => "warning_version_upgrade",
+            VersionChange::Downgrade => "warning_version_downgrade",
+            VersionChange::Reinstall => "warning_version_reinstall",
+        };
+        let confirmation = L10N.get_fmt(key, self.language, short_version(&version));
+        let accept = move |this: &mut Self| {
+            this.new_version = Some(version);
+            this.allow_downgrade = change == VersionChange::Downgrade;
+            this.start_update();
+        };
+
+        if change == VersionChange::Reinstall {
+            self.preflight(Operation::Update, move |this, preflight| {
+                this.set_detailed_confirmation(confirmation, "preflight_title", preflight, accept);
+            });
+            return;
+        }
+
+        // Список уже загружен: изменения берутся из него без запросов к источнику
+        let changelog = match self.versions_channel {
+            Some(Channel::Stable) => Some(Changelog::ReleaseNotes(self.versions[selected].notes.clone())),
+            _ => current_version.as_deref()
+                .and_then(|current_version| versions.iter().position(|version| same_commit(version, current_version)))
+                .map(|installed| {
+                    let range = match change {
+                        VersionChange::Downgrade => installed..selected,
+                        _ => selected..installed,
+                    };
+                    Changelog::Commits(self.versions[range].to_vec())
+                }),
+        };
+
+        let mut details = Vec::new();
+        if change == VersionChange::Downgrade && matches!(changelog, Some(Changelog::Commits(_))) {
+            details.push(Line::from(L10N.get("changelog_rollback", self.language)).style(Style::default().fg(Color::Red)));
+        }
+        details.extend(build_changelog(changelog.as_ref(), self.language));
+        self.preflight(Operation::Update, move |this, preflight| {
+            let details = with_preflight(preflight, details, this.language);
+            this.set_detailed_confirmation(confirmation, "changelog_title", details, accept);
+        });
+    }
+
+    fn handle_switch_channel(&mut self) {
+        let Some(current) = self.installed_channel() else {
+            return;
+        };
+
+        self.channel_switch_current = Some(current);
+        self.channel_switch_targets = Channel::value_variants()
+            .iter()
+            .copied()
+            .filter(|&channel| channel != current)
+            .collect();
+        self.channel_switch_state.select(Some(0));
+        self.set_view_state(ViewState::ChannelSwitch);
+    }
+
+    // Целевая версия - последняя в выбранном канале, её определение требует сети
+    fn select_switch_target(&mut self, target: Channel) {
+        if self.switch_receiver.is_some() {
+            return;
+        }
+
+        let source = update_check::release_source(&self.config);
+        if self.headless {
+            let version = update_check::latest_version(source.as_ref(), target).map_err(|e| e.to_string());
+            self.confirm_channel_switch(target, version);
+            return;
+        }
+
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let version = update_check::latest_version(source.as_ref(), target).map_err(|e| e.to_string());
+            tx.send((target, version)).unwrap_or_default();
+        });
+        self.switch_receiver = Some(rx);
+    }
+
+    fn switch_progress(&mut self) {
+        let Some(rx) = &self.switch_receiver else {
+            return;
+        };
+        let (target, version) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.switch_receiver = None;
+                return;
+            }
+        };
+
+        self.switch_receiver = None;
+        self.confirm_channel_switch(target, version);
+    }
+
+    fn confirm_channel_switch(&mut self, target: Channel, version: std::result::Result<String, String>) {
+        let version = match version {
+            Ok(version) => version,
+            Err(e) => {
+                self.set_error(L10N.get_fmt("switch_channel_version_error", self.language, &e));
+                return;
+            }
+        };
+        let Some(current) = self.channel_switch_current else {
+            return;
+        };
+
+        let confirmation = L10N.get_fmt_args("warning_switch_channel", self.language, &[
+            &L10N.get(current.title_key(), self.language),
+            &L10N.get(target.title_key(), self.language),
+            short_version(&version),
+        ]);
+
+        let mut details = vec![Line::from(L10N.get("warning_switch_channel_reinstall", self.language))];
+        if target.stability() > current.stability() {
+            details.push(Line::from(""));
+            details.push(Line::from(L10N.get("warning_switch_channel_downgrade", self.language)).style(Style::default().fg(Color::Yellow)));
+        }
+        self.preflight(Operation::Update, move |this, preflight| {
+            let details = with_preflight(preflight, details, this.language);
+            this.set_detailed_confirmation(confirmation, "switch_channel_warnings", details, move |this| {
+                this.start_channel_switch(target, version);
+            });
+        });
+    }
+
+    // Проверки перед запуском скрипта в фоне, как проверка обновлений: запрос к GitHub может ждать до таймаута.
+    // next вызывается с таблицей проверок, если критических ошибок нет
+    fn preflight<F>(&mut self, operation: Operation, next: F)
+    where
+        F: FnOnce(&mut Self, Vec<Line<'static>>) + 'static,
+    {
+        let config = self.config.clone();
+        let language = self.language;
+        let run = move || (preflight::run(&config, operation, language), preflight::pacman_lock(&config.system_root));
+
+        // Без интерфейса ожидание ничего не блокирует
+        if self.headless {
+            let (checks, lock) = run();
+            self.finish_preflight(checks, lock, Box::new(next));
+            return;
+        }
+
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            tx.send(run()).unwrap_or_default();
+        });
+        self.preflight_receiver = Some(rx);
+        self.preflight_next = Some(Box::new(next));
+    }
+
+    fn preflight_progress(&mut self) {
+        let Some(rx) = &self.preflight_receiver else {
+            return;
+        };
+        let (checks, lock) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.cancel_preflight();
+                return;
+            }
+        };
+
+        self.preflight_receiver = None;
+        if let Some(next) = self.preflight_next.take() {
+            self.finish_preflight(checks, lock, next);
+        }
+    }
+
+    fn cancel_preflight(&mut self) {
+        self.preflight_receiver = None;
+        self.preflight_next = None;
+    }
+
+    // Все проваленные проверки показываются одним отчётом; блокировку pacman можно снять или дождаться прямо из него
+    fn finish_preflight(&mut self, checks: Vec<PreflightCheck>, lock: PacmanLock, next: PreflightFn) {
+        let report = build_preflight(&checks, self.language);
+        // Пробный запуск показывает проваленные проверки в таблице, но не останавливается на них
+        if !preflight::has_failures(&checks) || self.dry_run {
+            next(self, report);
+            return;
+        }
+
+        match lock {
+            PacmanLock::Stale { age } if !self.headless => {
+                let age = age.map_or("?".to_string(), format_duration);
+                let confirmation = L10N.get_fmt("preflight_failed_lock_stale", self.language, &age);
+                self.set_detailed_confirmation(confirmation, "preflight_title", report, move |this| {
+                    this.remove_stale_lock(age);
+                });
+            }
+            PacmanLock::Held { process, pid } if !self.headless => {
+                let confirmation = L10N.get_fmt_args("preflight_failed_lock_held", self.language, &[&process, &pid.to_string()]);
+                self.set_detailed_confirmation(confirmation, "preflight_title", report, move |this| {
+                    this.start_lock_wait(process, pid);
+                });
+            }
+            _ => {
+                let failures: Vec<String> = checks.iter()
+                    .filter(|check| check.level == CheckLevel::Fail)
+                    .map(|check| format!("{}: {}", check.name, check.detail))
+                    .collect();
+                self.set_error(L10N.get_fmt("preflight_failed", self.language, &failures.join("\n")));
+            }
+        }
+    }
+
+    fn offer_lock_removal(&mut self, age: Option<Duration>) {
+        let age = age.map_or("?".to_string(), format_duration);
+        let confirmation = L10N.get_fmt("lock_stale_confirm", self.language, &age);
+        self.set_confirmation(confirmation, move |this| {
+            this.remove_stale_lock(age);
+        });
+    }
+
+    // Перед удалением состояние проверяется ещё раз: пакетный менеджер мог запуститься
+    fn remove_stale_lock(&mut self, age: String) {
+        if !matches!(preflight::pacman_lock(&self.config.system_root), PacmanLock::Stale { .. }) {
+            self.set_info(L10N.get("lock_not_stale", self.language));
+            return;
+        }
+
+        let path = preflight::pacman_lock_path(&self.config.system_root).to_string_lossy().into_owned();
+        self.script_note = Some(L10N.get_fmt_args("lock_remove_note", self.language, &[&path, &age]));
+        let args = vec!["rm".to_string(), "-v".to_string(), "--".to_string(), path];
+        self.run_command_progress("lock_remove_title", &[], "sudo", args);
+    }
+
+    fn start_lock_wait(&mut self, process: String, pid: u32) {
+        self.lock_wait = Some((process, pid, Instant::now()));
+        self.lock_wait_checked = Instant::now();
+    }
+
+    fn lock_wait_progress(&mut self) {
+        if self.lock_wait.is_none() || self.lock_wait_checked.elapsed() < LOCK_WAIT_INTERVAL {
+            return;
+        }
+        self.lock_wait_checked = Instant::now();
+
+        match preflight::pacman_lock(&self.config.system_root) {
+            // Блокировку мог перехватить другой пакетный менеджер
+            PacmanLock::Held { process, pid } => {
+                if let Some(wait) = &mut self.lock_wait {
+                    wait.0 = process;
+                    wait.1 = pid;
+                }
+            }
+            // Процесс завершился аварийно и оставил блокировку
+            PacmanLock::Stale { age } => {
+                self.lock_wait = None;
+                self.offer_lock_removal(age);
+            }
+            PacmanLock::Free => {
+                self.lock_wait = None;
+                self.set_info(L10N.get("lock_released", self.language));
+            }
+        }
+    }
+
+    // Смена канала - переустановка update.sh из другой ветки с записью нового BUILD_ID
+    fn start_channel_switch(&mut self, target: Channel, version: String) {
+        let mut args = self.script_command("update.sh");
+        args.extend([
+            "--no-confirm".to_string(),
+            "-t".to_string(),
+            target.as_arg().to_string(),
+            "-v".to_string(),
+            version.clone(),
+        ]);
+        args.extend(self.reboot_args());
+
+        if self.dry_run {
+            let plan = plan::update(&self.config, target, &version, &home_dir(), command_line("sudo", &args), !self.no_reboot);
+            self.show_plan(plan);
+            return;
+        }
+        self.run_update("switch_channel_progress_title", args);
+    }
+
+    fn handle_uninstall(&mut self) {
+        match self.os_release() {
+            Some(os_release) if os_release.is_kite() => {
+                self.set_view_state(ViewState::UninstallType);
+                self.uninstall_type_state.select(Some(0));
+            }
+            Some(os_release) => {
+                self.set_error(L10N.get_fmt("update_not_supported", self.language, os_release.display_name()));
+            }
+            None => {
+                self.set_error(L10N.get("system_not_detected", self.language));
+            }
+        }
+    }
+
+    fn handle_uninstall_type(&mut self) {
+        if let Some(selected) = self.uninstall_type_state.selected() {
+            let uninstall_type = self.uninstall_types[selected].1;
+            let confirmation = match uninstall_type {
+                UninstallType::Config => L10N.get("warning_uninstall_config", self.language),
+                UninstallType::Apps => L10N.get("warning_uninstall_apps", self.language),
+                UninstallType::Full => L10N.get("warning_uninstall_full", self.language),
+            };
+
+            self.set_confirmation(confirmation.to_string(), move |this| {
+                this.run_uninstall_script(uninstall_type);
+            });
+        }
+    }
+
+    fn preview_uninstall(&mut self) {
+        if let Some(selected) = self.uninstall_type_state.selected() {
+            let uninstall_type = self.uninstall_types[selected].1;
+            let plan = self.uninstall_plan(uninstall_type);
+            let details = build_plan(&plan, self.language);
+            self.set_detailed_confirmation(L10N.get("plan_preview_continue", self.language), "plan_title", details, |this| {
+                this.handle_uninstall_type();
+            });
+        }
+    }
+
+    fn uninstall_args(&self, uninstall_type: UninstallType) -> Vec<String> {
+        let mut args = self.script_command("uninstall.sh");
+        args.extend([uninstall_type.as_arg().to_string(), "--no-confirm".to_string()]);
+        args.extend(self.reboot_args());
+        args
+    }
+
+    fn uninstall_plan(&self, uninstall_type: UninstallType) -> Plan {
+        let command = command_line("sudo", &self.uninstall_args(uninstall_type));
+        plan::uninstall(&self.config, uninstall_type, &home_dir(), command, !self.no_reboot)
+    }
+
+    fn run_uninstall_script(&mut self, uninstall_type: UninstallType) {
+        if self.dry_run {
+            let plan = self.uninstall_plan(uninstall_type);
+            self.show_plan(plan);
+            return;
+        }
+        let args = self.uninstall_args(uninstall_type);
+        match uninstall_type {
+            UninstallType::Apps => self.run_command_progress("uninstall_progress_title", uninstall_type.steps(), "sudo", args),
+            UninstallType::Config | UninstallType::Full => {
+                self.backup_configs(&uninstall_type.backup_reason(), move |this, _| {
+                    this.run_command_progress("uninstall_progress_title", uninstall_type.steps(), "sudo", args);
+                });
+            }
+        }
+    }
+
+    // Архив настроек перед запуском, который их удалит; без архива запуск только с подтверждением
+    fn backup_configs<F>(&mut self, reason: &str, run: F)
+    where
+        F: FnOnce(&mut Self, Option<PathBuf>) + 'static,
+    {
+        match backup::create(&self.config.system_root, &home_dir(), reason) {
+            Ok(backup) => {
+                self.script_note = backup.as_ref().map(|path| L10N.get_fmt("backup_created", self.language, &path.display().to_string()));
+                run(self, backup);
+            }
+            Err(e) => {
+                let error = e.to_string();
+                let confirmation = L10N.get_fmt("backup_failed_continue", self.language, &error);
+                let note = L10N.get_fmt("backup_failed", self.language, &error);
+                self.set_confirmation(confirmation, move |this| {
+                    this.script_note = Some(note);
+                    run(this, None);
+                });
+            }
+        }
+    }
+
+    // Обновление как транзакция: снимок системы перед update.sh, при ошибке - откат к нему
+    fn run_update(&mut self, title: &'static str, args: Vec<String>) {
+        self.backup_configs(backup::UPDATE_REASON, move |this, config_backup| {
+            match Snapshot::take(&this.config.system_root, &home_dir(), config_backup) {
+                Ok(snapshot) => {
+                    this.transaction = Some(snapshot);
+                    this.run_command_progress(title, steps::UPDATE_STEPS, "sudo", args);
+                }
+                Err(e) => {
+                    let confirmation = L10N.get_fmt("snapshot_failed_continue", this.language, e.to_string().as_str());
+                    this.set_confirmation(confirmation, move |this| {
+                        this.run_command_progress(title, steps::UPDATE_STEPS, "sudo", args);
+                    });
+                }
+            }
+        });
+    }
+
+    // Завершение запуска: неудачное обновление откатывается, итог отката сообщается
+    fn complete_transaction(&mut self, failure: Option<String>) {
+        if let Some(rollback) = self.rollback.take() {
+            self.finish_rollback(rollback, failure);
+            return;
+        }
+        let Some(snapshot) = self.transaction.take() else {
+            return;
+        };
+        let Some(error) = failure else {
+            snapshot.discard();
+            return;
+        };
+
+        // Этап берётся из отслеживания [INFO], без интерфейса остаётся сообщение скрипта
+        let failure = self.script_steps.steps().iter()
+            .find(|step| step.state == StepState::Failed)
+            .map(|step| step.title.clone())
+            .unwrap_or(error);
+
+        let rollback = snapshot.rollback(&self.config.system_root);
+        if rollback.is_empty() {
+            snapshot.discard();
+            self.set_error(L10N.get_fmt("rollback_not_needed", self.language, &failure));
+            return;
+        }
+
+        let mut args = self.script_command("rollback.sh");
+        args.extend(snapshot.rollback_args(&rollback));
+        let steps = rollback.steps();
+        self.script_note = Some(L10N.get_fmt("rollback_started", self.language, &failure));
+        self.rollback = Some(RollbackRun {
+            failure,
+            actions: rollback.describe(self.language),
+            snapshot,
+            exit_code: self.exit_code,
+        });
+
+        // Окно вывода остаётся открытым: Enter после отката ведёт туда же, куда после обновления
+        let last_view_state = self.script_last_view_state;
+        self.run_command_progress("rollback_progress_title", &steps, "sudo", args);
+        self.script_last_view_state = last_view_state;
+    }
+
+    fn finish_rollback(&mut self, rollback: RollbackRun, failure: Option<String>) {
+        // Код выхода остаётся кодом обновления: оно не выполнено
+        if rollback.exit_code.is_some() {
+            self.exit_code = rollback.exit_code;
+        }
+
+        match failure {
+            None => {
+                rollback.snapshot.discard();
+                let actions = rollback.actions.join("; ");
+                self.set_error(L10N.get_fmt_args("rollback_done", self.language, &[&rollback.failure, &actions]));
+            }
+            Some(error) => {
+                let dir = rollback.snapshot.dir.display().to_string();
+                self.set_error(L10N.get_fmt_args("rollback_failed", self.language, &[&rollback.failure, &error, &dir]));
+            }
+        }
+    }
+
+    fn run_installation_script(&mut self) {
+        if let Some(selected) = self.installation_type_state.selected() {
+            let itype = self.installation_types[selected].1;
+            if self.dry_run {
+                let plan = self.installation_plan(itype);
+                self.show_plan(plan);
+                return;
+            }
+            let args = self.installation_args(itype);
+            self.run_command_progress("install_progress_title", steps::INSTALL_STEPS, "sudo", args);
+        }
+    }
+
+    fn preview_installation(&mut self) {
+        if let Some(selected) = self.installation_type_state.selected() {
+            let plan = self.installation_plan(self.installation_types[selected].1);
+            let details = build_plan(&plan, self.language);
+            self.set_detailed_confirmation(L10N.get("plan_preview_continue", self.language), "plan_title", details, |this| {
+                this.handle_installation_type();
+            });
+        }
+    }
+
+    fn installation_args(&self, itype: Channel) -> Vec<String> {
+        let mut args = self.script_command("install.sh");
+        args.extend([itype.as_arg().to_string(), "--no-confirm".to_string()]);
+        args.extend(self.reboot_args());
+        args
+    }
+
+    fn installation_plan(&self, itype: Channel) -> Plan {
+        let command = command_line("sudo", &self.installation_args(itype));
+        plan::install(&self.config, itype, command, !self.no_reboot, self.language)
+    }
+
+    // План вместо запуска: в терминале печатается, в интерфейсе показывается в окне
+    fn show_plan(&mut self, plan: Plan) {
+        let details = build_plan(&plan, self.language);
+        if self.headless {
+            for line in &details {
+                println!("{}", line_text(line));
+            }
+            return;
+        }
+        self.set_detailed_confirmation(L10N.get("plan_dry_run", self.language), "plan_title", details, |_| {});
+    }
+
+    // Аргументы для sudo или env: переменные окружения скриптов и путь к скрипту
+    fn script_command(&self, name: &str) -> Vec<String> {
+        let mut command = self.config.script_env();
+        command.push(self.config.script_path(name));
+        command
+    }
+
+    fn load_history(&mut self) {
+        self.history = history::list();
+        self.history_state.select((!self.history.is_empty()).then_some(0));
+        self.set_view_state(ViewState::History);
+    }
+
+    // Журнал открывается в окне вывода, как завершённый запуск
+    fn open_history_record(&mut self) {
+        let Some(record) = self.history_state.selected().and_then(|selected| self.history.get(selected)) else {
+            return;
+        };
+
+        let output = match history::read_output(&record.path) {
+            Ok(output) => output,
+            Err(e) => {
+                self.set_error(L10N.get_fmt("history_read_error", self.language, e.to_string().as_str()));
+                return;
+            }
+        };
+
+        let mut parser = AnsiLineBuffer::new();
+        let mut lines = parser.feed(&String::from_utf8_lossy(&output));
+        lines.extend(parser.finish());
+
+        self.script_result = match record.exit_code {
+            Some(Some(0)) => Some(ScriptResult::Success),
+            Some(_) => Some(ScriptResult::Failure(L10N.get("command_error", self.language))),
+            None => None,
+        };
+        self.script_output = lines;
+        self.script_partial_line = None;
+        self.script_steps = StepTracker::default();
+        self.script_show_log = true;
+        self.script_title = "history_title";
+        self.scroll_position = 0;
+        self.script_follow = false;
+        self.script_last_view_state = ViewState::History;
+        self.set_view_state(ViewState::ScriptProgress);
+    }
+
+    fn load_backups(&mut self) {
+        self.backups = backup::list();
+        self.backups_state.select((!self.backups.is_empty()).then_some(0));
+        self.set_view_state(ViewState::Backups);
+    }
+
+    fn selected_backup(&self) -> Option<&Backup> {
+        self.backups_state.selected().and_then(|selected| self.backups.get(selected))
+    }
+
+    fn open_backup(&mut self) {
+        let Some(backup) = self.selected_backup() else {
+            return;
+        };
+
+        match backup::contents(&backup.path) {
+            Ok(paths) => {
+                self.backup_entries = paths.into_iter().map(|path| (path, true)).collect();
+                self.backup_entries_state.select((!self.backup_entries.is_empty()).then_some(0));
+                self.set_view_state(ViewState::BackupEntries);
+            }
+            Err(e) => self.set_error(L10N.get_fmt("backup_read_error", self.language, e.to_string().as_str())),
+        }
+    }
+
+    fn toggle_backup_entry(&mut self) {
+        let selected = self.backup_entries_state.selected();
+        if let Some((_, restore)) = selected.and_then(|selected| self.backup_entries.get_mut(selected)) {
+            *restore = !*restore;
+        }
+    }
+
+    // Отмеченные пути распаковываются поверх текущих; файлы, которых нет в архиве, остаются
+    fn confirm_restore(&mut self) {
+        let Some(archive) = self.selected_backup().map(|backup| backup.path.clone()) else {
+            return;
+        };
+        let paths: Vec<PathBuf> = self.backup_entries.iter()
+            .filter(|(_, restore)| *restore)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        if paths.is_empty() {
+            self.set_error(L10N.get("restore_nothing_selected", self.language));
+            return;
+        }
+
+        let details = paths.iter().map(|path| Line::from(format!("  {}", path.display()))).collect();
+        let name = archive.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let confirmation = L10N.get_fmt("warning_restore", self.language, &name);
+        self.set_detailed_confirmation(confirmation, "restore_paths", details, move |this| {
+            let args = backup::restore_args(&archive, &paths);
+            this.run_command_progress("restore_progress_title", &[], "sudo", args);
+        });
+    }
+
+    fn confirm_delete_backup(&mut self) {
+        let Some(archive) = self.selected_backup().map(|backup| backup.path.clone()) else {
+            return;
+        };
+
+        let name = archive.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        self.set_confirmation(L10N.get_fmt("warning_delete_backup", self.language, &name), move |this| {
+            if let Err(e) = backup::delete(&archive) {
+                this.set_error(L10N.get_fmt("backup_delete_error", this.language, e.to_string().as_str()));
+            }
+            this.load_backups();
+        });
+    }
+
+    fn reboot_args(&self) -> Option<String> {
+        self.no_reboot.then(|| "--no-reboot".to_string())
+    }
+}
+
+// Копирует вывод программы в терминал и журнал без ожидания конца строки
+fn spawn_output_tee<R, W>(mut output: R, mut terminal: W, log: Option<RunLog>) -> thread::JoinHandle<Vec<String>>
+where
+    R: io::Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        let mut tail = OutputTail::default();
+        while let Ok(count) = output.read(&mut buffer) {
+            if count == 0 {
+                break;
+            }
+
+            terminal.write_all(&buffer[..count]).and_then(|_| terminal.flush()).unwrap_or_default();
+            if let Some(log) = &log {
+                log.write(&buffer[..count]);
+            }
+            tail.push_bytes(&buffer[..count]);
+        }
+        tail.into_lines()
+    })
+}
+
+// Имя программы для сообщений: для sudo и env - запускаемый скрипт
+fn command_name(program: &OsStr, args: &[String]) -> String {
+    let program = Path::new(program);
+    let target = match program.file_name() {
+        Some(name) if name == "sudo" || name == "env" => args.iter()
+            .find(|arg| !arg.contains('='))
+            .map(Path::new)
+            .unwrap_or(program),
+        _ => program,
+    };
+
+    target.file_name()
+        .unwrap_or(target.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Командная строка для показа: аргументы с пробелами и спецсимволами в кавычках
+fn command_line(program: &str, args: &[String]) -> String {
+    let quote = |arg: &str| match arg.chars().any(|c| c.is_whitespace() || "'\"$`\\*?;&|<>()".contains(c)) {
+        true => format!("'{}'", arg.replace('\'', "'\\''")),
+        false => arg.to_string(),
+    };
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Домашний каталог пользователя, от имени которого скрипты запускаются через sudo
+fn home_dir() -> PathBuf {
+    std::env::var_os("HOME").map_or(PathBuf::from("/root"), PathBuf::from)
+}
+
+fn command_state(name: String, started: Instant, status: io::Result<ExitStatus>, output_tail: Vec<String>) -> CommandState {
+    match status {
+        Ok(status) if status.success() => CommandState::Completed,
+        Ok(status) => CommandState::Exit(CommandOutcome::new(name, status, started.elapsed(), output_tail)),
+        Err(e) => CommandState::WaitError(e),
+    }
+}
+
+// Извлекает из буфера корректный UTF-8, оставляя незавершённый символ для следующего чтения
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+
+    let text = String::from_utf8_lossy(&pending[..valid]).into_owned();
+    pending.drain(..valid);
+    text
+}
+
+fn run_tui(config: Config, open_update: bool) -> Result<()> {
     enable_raw_mode()?;
     execute!(io::stdout(), EnterAlternateScreen)?;
     
@@ -664,16 +2145,22 @@ fn run_tui() -> Result<()> {
     execute!(io::stdout(), crossterm::terminal::Clear(crossterm::terminal::ClearType::All))?;
 
     let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
-    let mut app = App::new();
+    let mut app = App::new(config);
     let mut should_quit = false;
-    let mut scroll_state = ScrollbarState::new(0);
+
+    if open_update {
+        app.handle_update();
+    }
 
     while !should_quit {
         // Обновляем прогресс скрипта
         app.update_script_progress();
-
-        // Обновляем состояние скроллбара
-        scroll_state = scroll_state.content_length(app.script_output.len());
+        app.update_check_progress();
+        app.versions_progress();
+        app.switch_progress();
+        app.lock_wait_progress();
+        app.preflight_progress();
+        app.terminate_progress();
 
         if app.terminal_clear {
             terminal.clear()?;
@@ -710,7 +2197,13 @@ fn run_tui() -> Result<()> {
                         .highlight_style(Style::default().bg(Color::DarkGray))
                         .highlight_symbol(">> ");
 
-                    frame.render_stateful_widget(menu, chunks[1], &mut app.menu_state);
+                    let menu_chunks = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .split(chunks[1]);
+
+                    frame.render_stateful_widget(menu, menu_chunks[0], &mut app.menu_state);
+                    build_summary(frame, menu_chunks[1], &app.system_summary, app.last_update_check.as_ref(), app.language);
 
                     // Статус
                     let status = Paragraph::new(app.status.clone())
@@ -780,46 +2273,68 @@ fn run_tui() -> Result<()> {
 
                     build_hints(frame, chunks, L10N.get("custom_package_input_navigation_hints", app.language));
                 }
-                ViewState::_ScriptProgress => {
+                ViewState::ScriptProgress => {
                     let chunks = Layout::default()
                         .direction(Direction::Vertical)
                         .constraints([
                             Constraint::Length(3),
                             Constraint::Min(10),
                             Constraint::Length(3),
+                            Constraint::Length(3),
                         ])
                         .split(frame.area());
 
-                    let title = Paragraph::new(L10N.get("script_progress_title", app.language))
+                    let title = Paragraph::new(L10N.get(app.script_title, app.language))
                         .block(Block::default().borders(Borders::ALL))
                         .alignment(Alignment::Center);
                     frame.render_widget(title, chunks[0]);
 
-                    let output_text = app.script_output
-                        .iter()
-                        .rev()
-                        .take(frame.area().height as usize - 8)
-                        .rev()
-                        .cloned()
-                        .collect::<Vec<String>>()
-                        .join("\n");
+                    if app.script_show_log {
+                        let mut output_lines = app.script_output.clone();
+                        output_lines.extend(app.script_partial_line.clone());
 
-                    let output = Paragraph::new(output_text)
-                        .block(Block::default().borders(Borders::ALL).title(L10N.get("script_output", app.language)))
-                        .wrap(Wrap { trim: true })
-                        .scroll((app.scroll_position as u16, 0));
+                        // Прокрутка считается по экранным строкам с учетом переносов
+                        let width = chunks[1].width.saturating_sub(2);
+                        let height = chunks[1].height.saturating_sub(2) as usize;
+                        let output = Paragraph::new(output_lines).wrap(Wrap { trim: false });
 
-                    frame.render_widget(output, chunks[1]);
+                        let max_scroll = output.line_count(width).saturating_sub(height);
+                        if app.script_follow || app.scroll_position >= max_scroll {
+                            app.scroll_position = max_scroll;
+                            app.script_follow = true;
+                        }
 
-                    // Рендерим скроллбар
-                    frame.render_stateful_widget(
-                        Scrollbar::default()
-                            .orientation(ScrollbarOrientation::VerticalRight)
-                            .begin_symbol(Some("↑"))
-                            .end_symbol(Some("↓")),
-                        chunks[1],
-                        &mut scroll_state,
-                    );
+                        let output = output
+                            .block(Block::default().borders(Borders::ALL).title(L10N.get("script_output", app.language)))
+                            .scroll((app.scroll_position as u16, 0));
+
+                        frame.render_widget(output, chunks[1]);
+
+                        // Рендерим скроллбар
+                        let mut scroll_state = ScrollbarState::new(max_scroll).position(app.scroll_position);
+                        frame.render_stateful_widget(
+                            Scrollbar::default()
+                                .orientation(ScrollbarOrientation::VerticalRight)
+                                .begin_symbol(Some("↑"))
+                                .end_symbol(Some("↓")),
+                            chunks[1],
+                            &mut scroll_state,
+                        );
+                    } else {
+                        build_steps(frame, chunks[1], &app.script_steps, app.script_partial_line.as_ref().or(app.script_output.last()), app.language);
+                    }
+
+                    // Итог выполнения
+                    let (result_text, result_color) = match &app.script_result {
+                        None => (L10N.get("script_running", app.language), Color::Yellow),
+                        Some(ScriptResult::Success) => (L10N.get("command_success", app.language), Color::Green),
+                        Some(ScriptResult::Failure(error)) => (error.clone(), Color::Red),
+                    };
+                    let result = Paragraph::new(result_text)
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get("status", app.language)))
+                        .style(Style::default().fg(result_color))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(result, chunks[2]);
 
                     let hints = match app.script_process {
                         Some(_) => L10N.get("script_running_hints", app.language),
@@ -848,8 +2363,8 @@ fn run_tui() -> Result<()> {
                         .iter()
                         .map(|(name, _, desc)| {
                             ListItem::new(vec![
-                                Line::from(L10N.get(*name, app.language)),
-                                Line::from(format!("  {}", textwrap::fill(L10N.get(*desc, app.language).as_str(), 60))),
+                                Line::from(L10N.get(name, app.language)),
+                                Line::from(format!("  {}", textwrap::fill(L10N.get(desc, app.language).as_str(), 60))),
                             ])
                         })
                         .collect();
@@ -891,8 +2406,7 @@ fn run_tui() -> Result<()> {
                         .take((area.height as usize).saturating_sub(8))
                         .rev()
                         .cloned()
-                        .collect::<Vec<String>>()
-                        .join("\n");
+                        .collect::<Vec<Line>>();
 
                     let output = Paragraph::new(output_text)
                         .block(Block::default().borders(Borders::ALL).title(L10N.get("available_updates", app.language)))
@@ -902,75 +2416,350 @@ fn run_tui() -> Result<()> {
                     let hints = L10N.get("update_check_hints", app.language);
 
                     build_hints(frame, chunks, hints);
+                }
+                ViewState::UninstallType => {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(3),
+                            Constraint::Min(10),
+                            Constraint::Length(5),
+                            Constraint::Length(3),
+                        ])
+                        .split(frame.area());
 
-                    if app.script_receiver.is_none() {
-                        if !app.show_error {
-                            let version = app.script_output.iter()
-                                .map(|v| v.trim())
-                                .collect::<String>();
-
-                            let current_version = get_os_version()
-                                .unwrap_or("0.0.0".to_string());
-
-                            if !version.is_empty() && current_version != version {
-                                let confirmation = L10N.get_fmt("warning_update_found", app.language, version.as_str());
-                                app.set_confirmation(confirmation, move |this| {
-                                    this.set_view_state(ViewState::UpdateCheck);
-                                    this.new_version = Some(version);
-                                    this.run_selected_action();
-                                });
-                            } else {
-                                let confirmation = L10N.get("version_up_to_date", app.language).to_string();
-                                app.set_confirmation(confirmation, move |this| {
-                                    this.set_view_state(ViewState::MainMenu);
-                                });
-                            }
-                        }
+                    let title = Paragraph::new(L10N.get("select_uninstall_type", app.language))
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(title, chunks[0]);
 
-                        app.set_view_state(app.script_last_view_state);
+                    let items: Vec<ListItem> = app.uninstall_types
+                        .iter()
+                        .map(|(name, _, desc)| {
+                            ListItem::new(vec![
+                                Line::from(L10N.get(name, app.language)),
+                                Line::from(format!("  {}", textwrap::fill(L10N.get(desc, app.language).as_str(), 60))),
+                            ])
+                        })
+                        .collect();
+
+                    let uninstall_list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get("available_installation_types", app.language)))
+                        .highlight_style(Style::default().bg(Color::DarkGray))
+                        .highlight_symbol(">> ");
+
+                    frame.render_stateful_widget(uninstall_list, chunks[1], &mut app.uninstall_type_state);
+
+                    if !app.show_confirmation {
+                        build_hints(frame, chunks, L10N.get("uninstall_type_navigation_hints", app.language));
+                    }
+                }
+                ViewState::History => {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(3),
+                            Constraint::Min(10),
+                            Constraint::Length(3),
+                        ])
+                        .split(frame.area());
+
+                    let title = Paragraph::new(L10N.get("history_title", app.language))
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(title, chunks[0]);
+
+                    let items: Vec<ListItem> = app.history
+                        .iter()
+                        .map(|record| {
+                            let (result, color) = match (record.exit_code, record.signal) {
+                                (Some(Some(0)), _) => ("✔ 0".to_string(), Color::Green),
+                                (Some(Some(code)), _) => (format!("✘ {}", code), Color::Red),
+                                (Some(None), Some(signal)) => (format!("✘ SIG{}", signal), Color::Red),
+                                (Some(None), None) => ("✘ -".to_string(), Color::Red),
+                                (None, _) => ("?".to_string(), Color::Yellow),
+                            };
+
+                            ListItem::new(Line::from(vec![
+                                Span::raw(format!("{}  ", record.started)),
+                                Span::styled(format!("{:<7}", result), Style::default().fg(color)),
+                                Span::raw(format!("{:>6}  ", record.duration.as_deref().unwrap_or("-"))),
+                                Span::raw(record.command.clone()),
+                            ]))
+                        })
+                        .collect();
+
+                    let history_list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get("history_runs", app.language)))
+                        .highlight_style(Style::default().bg(Color::DarkGray))
+                        .highlight_symbol(">> ");
+
+                    frame.render_stateful_widget(history_list, chunks[1], &mut app.history_state);
+
+                    build_hints(frame, chunks, L10N.get("history_navigation_hints", app.language));
+                }
+                ViewState::Backups => {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(3),
+                            Constraint::Min(10),
+                            Constraint::Length(3),
+                        ])
+                        .split(frame.area());
+
+                    let title = Paragraph::new(L10N.get("restore_title", app.language))
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(title, chunks[0]);
+
+                    let items: Vec<ListItem> = app.backups
+                        .iter()
+                        .map(|backup| ListItem::new(Line::from(vec![
+                            Span::raw(format!("{}  ", backup.created.format("%Y-%m-%d %H:%M:%S"))),
+                            Span::raw(format!("{:<18}", backup.reason)),
+                            Span::styled(format!("{:>10}", backup::format_size(backup.size)), Style::default().fg(Color::DarkGray)),
+                        ])))
+                        .collect();
+
+                    let block_title = match backup::backup_dir() {
+                        Some(dir) => format!("{} ({})", L10N.get("restore_backups", app.language), dir.display()),
+                        None => L10N.get("restore_backups", app.language),
+                    };
+                    let backups_list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(block_title))
+                        .highlight_style(Style::default().bg(Color::DarkGray))
+                        .highlight_symbol(">> ");
+
+                    frame.render_stateful_widget(backups_list, chunks[1], &mut app.backups_state);
+
+                    if !app.show_confirmation {
+                        build_hints(frame, chunks, L10N.get("restore_navigation_hints", app.language));
+                    }
+                }
+                ViewState::BackupEntries => {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(3),
+                            Constraint::Min(10),
+                            Constraint::Length(3),
+                        ])
+                        .split(frame.area());
+
+                    let archive = app.selected_backup()
+                        .and_then(|backup| backup.path.file_name())
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let title = Paragraph::new(archive)
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(title, chunks[0]);
+
+                    let items: Vec<ListItem> = app.backup_entries
+                        .iter()
+                        .map(|(path, restore)| {
+                            let prefix = if *restore { "[X] " } else { "[ ] " };
+                            let state = match path.symlink_metadata().is_ok() {
+                                true => L10N.get("restore_overwrite", app.language),
+                                false => L10N.get("restore_missing", app.language),
+                            };
+                            ListItem::new(Line::from(vec![
+                                Span::raw(format!("{}{}  ", prefix, path.display())),
+                                Span::styled(state, Style::default().fg(Color::DarkGray)),
+                            ]))
+                        })
+                        .collect();
+
+                    let entries_list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get("restore_paths", app.language)))
+                        .highlight_style(Style::default().bg(Color::DarkGray))
+                        .highlight_symbol(">> ");
+
+                    frame.render_stateful_widget(entries_list, chunks[1], &mut app.backup_entries_state);
+
+                    if !app.show_confirmation {
+                        build_hints(frame, chunks, L10N.get("restore_entries_navigation_hints", app.language));
                     }
                 }
-                ViewState::UninstallType => {
+                ViewState::ChannelSwitch => {
                     let chunks = Layout::default()
                         .direction(Direction::Vertical)
                         .constraints([
                             Constraint::Length(3),
-                            Constraint::Min(10),
-                            Constraint::Length(5),
+                            Constraint::Length(4),
+                            Constraint::Min(8),
                             Constraint::Length(3),
                         ])
                         .split(frame.area());
 
-                    let title = Paragraph::new(L10N.get("select_uninstall_type", app.language))
+                    let title = Paragraph::new(L10N.get("switch_channel_title", app.language))
                         .block(Block::default().borders(Borders::ALL))
                         .alignment(Alignment::Center);
                     frame.render_widget(title, chunks[0]);
 
-                    let items: Vec<ListItem> = app.uninstall_types
+                    let current = app.channel_switch_current
+                        .map(|channel| L10N.get(channel.title_key(), app.language))
+                        .unwrap_or_default();
+                    let version = app.system_summary.version.as_deref().map_or("-", short_version);
+                    let status = match app.switch_receiver.is_some() {
+                        true => Line::from(L10N.get("switch_channel_resolving", app.language)).style(Style::default().fg(Color::Yellow)),
+                        false => Line::from(""),
+                    };
+                    let current_info = Paragraph::new(vec![
+                        Line::from(L10N.get_fmt_args("switch_channel_current", app.language, &[&current, version])),
+                        status,
+                    ])
+                    .block(Block::default().borders(Borders::ALL));
+                    frame.render_widget(current_info, chunks[1]);
+
+                    let items: Vec<ListItem> = app.channel_switch_targets
                         .iter()
-                        .map(|(name, _, desc)| {
+                        .map(|channel| {
                             ListItem::new(vec![
-                                Line::from(L10N.get(*name, app.language)),
-                                Line::from(format!("  {}", textwrap::fill(L10N.get(*desc, app.language).as_str(), 60))),
+                                Line::from(L10N.get(channel.title_key(), app.language)),
+                                Line::from(format!("  {}", textwrap::fill(L10N.get(channel.description_key(), app.language).as_str(), 60))),
                             ])
                         })
                         .collect();
 
-                    let uninstall_list = List::new(items)
-                        .block(Block::default().borders(Borders::ALL).title(L10N.get("available_installation_types", app.language)))
+                    let targets = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get("switch_channel_targets", app.language)))
                         .highlight_style(Style::default().bg(Color::DarkGray))
                         .highlight_symbol(">> ");
+                    frame.render_stateful_widget(targets, chunks[2], &mut app.channel_switch_state);
 
-                    frame.render_stateful_widget(uninstall_list, chunks[1], &mut app.uninstall_type_state);
+                    if !app.show_confirmation {
+                        build_hints(frame, chunks, L10N.get("switch_channel_navigation_hints", app.language));
+                    }
+                }
+                ViewState::Versions => {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(3),
+                            Constraint::Min(10),
+                            Constraint::Length(3),
+                        ])
+                        .split(frame.area());
+
+                    let channel = app.versions_channel
+                        .map(|channel| L10N.get(channel.title_key(), app.language))
+                        .unwrap_or_default();
+                    let title = Paragraph::new(L10N.get_fmt("versions_title", app.language, &channel))
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(title, chunks[0]);
+
+                    let versions_chunks = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .split(chunks[1]);
+
+                    let list_block = Block::default().borders(Borders::ALL).title(L10N.get("versions_list", app.language));
+                    if app.versions_receiver.is_some() || app.versions.is_empty() {
+                        let key = match app.versions_receiver.is_some() {
+                            true => "versions_loading",
+                            false => "versions_empty",
+                        };
+                        let placeholder = Paragraph::new(L10N.get(key, app.language)).block(list_block);
+                        frame.render_widget(placeholder, versions_chunks[0]);
+                    } else {
+                        let installed = app.system_summary.version.as_deref();
+                        let items: Vec<ListItem> = app.versions
+                            .iter()
+                            .map(|entry| {
+                                let is_installed = installed.is_some_and(|installed| {
+                                    same_commit(&entry.version, installed)
+                                        || compare_releases(&entry.version, installed) == Some(Ordering::Equal)
+                                });
+                                let (marker, color) = match is_installed {
+                                    true => ("● ", Color::Green),
+                                    false => ("  ", Color::Reset),
+                                };
+
+                                ListItem::new(Line::from(vec![
+                                    Span::styled(marker, Style::default().fg(color)),
+                                    Span::styled(format!("{:<12}", short_version(&entry.version)), Style::default().fg(color)),
+                                    Span::raw(format!("{:<12}", entry.date.as_deref().unwrap_or("-"))),
+                                    Span::raw(entry.title.clone()),
+                                ]))
+                            })
+                            .collect();
+
+                        let versions_list = List::new(items)
+                            .block(list_block)
+                            .highlight_style(Style::default().bg(Color::DarkGray))
+                            .highlight_symbol(">> ");
+                        frame.render_stateful_widget(versions_list, versions_chunks[0], &mut app.versions_state);
+                    }
+
+                    let details = app.versions_state.selected()
+                        .and_then(|selected| app.versions.get(selected))
+                        .map(|entry| build_version_details(entry, app.language))
+                        .unwrap_or_default();
+                    let details = Paragraph::new(details)
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get("versions_details", app.language)))
+                        .wrap(Wrap { trim: false });
+                    frame.render_widget(details, versions_chunks[1]);
 
                     if !app.show_confirmation {
-                        build_hints(frame, chunks, L10N.get("uninstall_type_navigation_hints", app.language));
+                        build_hints(frame, chunks, L10N.get("versions_navigation_hints", app.language));
                     }
                 }
             }
 
-            // Подтверждение (если есть)
-            if app.show_confirmation {
+            // Подтверждение со списком изменений
+            if app.show_confirmation && app.confirmation_details.is_some() {
+                if let (Some(confirmation), Some(details)) = (&app.confirmation, &app.confirmation_details) {
+                    let area = centered_rect(80, 80, frame.area());
+                    frame.render_widget(Clear, area);
+
+                    let block = Block::default().borders(Borders::ALL).title(L10N.get("confirmation", app.language));
+                    let inner = block.inner(area);
+                    frame.render_widget(block, area);
+
+                    let message = Paragraph::new(confirmation.as_str())
+                        .alignment(Alignment::Center)
+                        .wrap(Wrap { trim: true });
+                    let message_height = message.line_count(inner.width) as u16;
+
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(message_height),
+                            Constraint::Min(3),
+                            Constraint::Length(1),
+                        ])
+                        .split(inner);
+                    frame.render_widget(message, chunks[0]);
+
+                    let width = chunks[1].width.saturating_sub(2);
+                    let height = chunks[1].height.saturating_sub(2) as usize;
+                    let changes = Paragraph::new(details.clone()).wrap(Wrap { trim: false });
+                    let max_scroll = changes.line_count(width).saturating_sub(height);
+                    app.confirmation_scroll = app.confirmation_scroll.min(max_scroll);
+
+                    let changes = changes
+                        .block(Block::default().borders(Borders::ALL).title(L10N.get(app.confirmation_details_title, app.language)))
+                        .scroll((app.confirmation_scroll as u16, 0));
+                    frame.render_widget(changes, chunks[1]);
+
+                    let mut scroll_state = ScrollbarState::new(max_scroll).position(app.confirmation_scroll);
+                    frame.render_stateful_widget(
+                        Scrollbar::default()
+                            .orientation(ScrollbarOrientation::VerticalRight)
+                            .begin_symbol(Some("↑"))
+                            .end_symbol(Some("↓")),
+                        chunks[1],
+                        &mut scroll_state,
+                    );
+
+                    let hints = Paragraph::new(L10N.get("changelog_hints", app.language))
+                        .alignment(Alignment::Center);
+                    frame.render_widget(hints, chunks[2]);
+                }
+            } else if app.show_confirmation {
                 if let Some(confirmation) = &app.confirmation {
                     let confirmation_text = L10N.get_fmt("uninstall_confirmation", app.language, confirmation);
                     let confirmation_block = Paragraph::new(confirmation_text)
@@ -984,6 +2773,28 @@ fn run_tui() -> Result<()> {
                 }
             }
 
+            // Запрос ввода для программы (если есть)
+            if app.show_input {
+                let value = match app.input_masked {
+                    true => "*".repeat(app.input_value.chars().count()),
+                    false => app.input_value.clone(),
+                };
+                let input_text = vec![
+                    Line::from(app.input_prompt.clone()),
+                    Line::from(""),
+                    Line::from(format!("> {}", value)).style(Style::default().fg(Color::Yellow)),
+                    Line::from(""),
+                    Line::from(L10N.get("input_hints", app.language)),
+                ];
+                let input_block = Paragraph::new(input_text)
+                    .block(Block::default().borders(Borders::ALL).title(L10N.get("input_title", app.language)))
+                    .wrap(Wrap { trim: false });
+
+                let input_area = centered_rect(60, 25, frame.area());
+                frame.render_widget(Clear, input_area); // Очищаем область под сообщением
+                frame.render_widget(input_block, input_area);
+            }
+
             // Ошибка (если есть)
             if app.show_error {
                 if let Some(error) = &app.error {
@@ -999,6 +2810,35 @@ fn run_tui() -> Result<()> {
                 }
             }
 
+            // Предварительные проверки в фоне
+            if app.preflight_receiver.is_some() {
+                let preflight_block = Paragraph::new(L10N.get("preflight_running", app.language))
+                    .block(Block::default().borders(Borders::ALL).title(L10N.get("preflight_title", app.language)))
+                    .style(Style::default().fg(Color::Yellow))
+                    .wrap(Wrap { trim: true });
+
+                let preflight_area = centered_rect(60, 20, frame.area());
+                frame.render_widget(Clear, preflight_area);
+                frame.render_widget(preflight_block, preflight_area);
+            }
+
+            // Ожидание освобождения базы pacman
+            if let Some((process, pid, started)) = &app.lock_wait {
+                let message = L10N.get_fmt_args("lock_wait_message", app.language, &[
+                    process,
+                    &pid.to_string(),
+                    &format_duration(started.elapsed()),
+                ]);
+                let wait_block = Paragraph::new(message)
+                    .block(Block::default().borders(Borders::ALL).title(L10N.get("lock_wait_title", app.language)))
+                    .style(Style::default().fg(Color::Yellow))
+                    .wrap(Wrap { trim: true });
+
+                let wait_area = centered_rect(60, 20, frame.area());
+                frame.render_widget(Clear, wait_area);
+                frame.render_widget(wait_block, wait_area);
+            }
+
             // Информационное окно (если есть)
             if app.show_info {
                 if let Some(info) = &app.info_message {
@@ -1018,20 +2858,37 @@ fn run_tui() -> Result<()> {
         // Добавляем неблокирующее чтение событий
         if crossterm::event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if app.show_error {
+                if app.preflight_receiver.is_some() {
+                    if key.code == KeyCode::Esc {
+                        app.cancel_preflight();
+                    }
+                } else if app.lock_wait.is_some() {
+                    if key.code == KeyCode::Esc {
+                        app.lock_wait = None;
+                    }
+                } else if app.show_error {
                     match key.code {
                         KeyCode::Enter | KeyCode::Esc => app.hide_error(),
                         _ => {}
                     }
+                } else if app.show_input {
+                    match key.code {
+                        KeyCode::Char(c) => app.input_value.push(c),
+                        KeyCode::Backspace => { app.input_value.pop(); }
+                        KeyCode::Enter => app.submit_input(),
+                        // Окно только скрывается: прервать задачу можно отменой, окно снова открывает i
+                        KeyCode::Esc => app.dismiss_input(),
+                        _ => {}
+                    }
                 } else if app.show_confirmation {
                     match key.code {
-                        KeyCode::Enter => {
-                            if let Some(confirmation_fn) = app.confirmation_fn.take() {
-                                confirmation_fn(&mut app);
-                            }
-                            app.hide_confirmation();
-                        },
+                        KeyCode::Enter => app.accept_confirmation(),
                         KeyCode::Esc => app.hide_confirmation(),
+                        // Прокрутка списка изменений; верхняя граница уточняется при отрисовке
+                        KeyCode::Up => app.confirmation_scroll = app.confirmation_scroll.saturating_sub(1),
+                        KeyCode::Down => app.confirmation_scroll += 1,
+                        KeyCode::PageUp => app.confirmation_scroll = app.confirmation_scroll.saturating_sub(10),
+                        KeyCode::PageDown => app.confirmation_scroll += 10,
                         _ => {}
                     }
                 } else if app.show_info {
@@ -1083,7 +2940,7 @@ fn run_tui() -> Result<()> {
                                 KeyCode::Char(' ') => app.toggle_package(),
                                 KeyCode::Enter => {
                                     if app.selected_packages.iter().any(|&selected| selected) {
-                                        app.start_package_installation();
+                                        app.install_selected_packages();
                                     } else {
                                         app.toggle_package();
                                     }
@@ -1101,39 +2958,36 @@ fn run_tui() -> Result<()> {
                                 _ => {}
                             }
                         }
-                        ViewState::_ScriptProgress => {
+                        ViewState::ScriptProgress => {
                             match key.code {
-                                KeyCode::Enter => {
-                                    if app.script_process.is_none() {
-                                        app.set_view_state(app.script_last_view_state);
-                                    }
-                                }
-                                KeyCode::Esc => {
-                                    if let Some(pid) = app.script_process.take() {
-                                        let system = System::new_all();
-                                        if let Some(process) = system.process(Pid::from_u32(pid)) {
-                                            process.kill();
-                                        }
-
-                                        app.status = L10N.get("task_cancelled", app.language);
-                                    }
+                                KeyCode::Enter if app.script_process.is_none() => {
                                     app.set_view_state(app.script_last_view_state);
                                 }
+                                KeyCode::Esc => app.request_cancel_script(app.script_last_view_state),
                                 KeyCode::Up => {
+                                    app.script_follow = false;
                                     app.scroll_position = app.scroll_position.saturating_sub(1);
-                                    scroll_state = scroll_state.position(app.scroll_position);
                                 }
                                 KeyCode::Down => {
                                     app.scroll_position = app.scroll_position.saturating_add(1);
-                                    scroll_state = scroll_state.position(app.scroll_position);
                                 }
                                 KeyCode::PageUp => {
+                                    app.script_follow = false;
                                     app.scroll_position = app.scroll_position.saturating_sub(10);
-                                    scroll_state = scroll_state.position(app.scroll_position);
                                 }
                                 KeyCode::PageDown => {
                                     app.scroll_position = app.scroll_position.saturating_add(10);
-                                    scroll_state = scroll_state.position(app.scroll_position);
+                                }
+                                KeyCode::Home => {
+                                    app.script_follow = false;
+                                    app.scroll_position = 0;
+                                }
+                                KeyCode::End => app.script_follow = true,
+                                KeyCode::Char('l') if !app.script_steps.is_empty() => {
+                                    app.script_show_log = !app.script_show_log;
+                                }
+                                KeyCode::Char('i') if app.script_input.is_some() => {
+                                    app.show_input_prompt(String::new(), false);
                                 }
                                 _ => {}
                             }
@@ -1162,24 +3016,14 @@ fn run_tui() -> Result<()> {
                                     app.installation_type_state.select(Some(i));
                                 }
                                 KeyCode::Enter => app.run_selected_action(),
+                                KeyCode::Char('p') => app.preview_installation(),
                                 KeyCode::Esc => app.set_view_state(ViewState::MainMenu),
                                 _ => {}
                             }
                         }
                         ViewState::UpdateCheck => {
-                            match key.code {
-                                KeyCode::Esc => {
-                                    if let Some(pid) = app.script_process.take() {
-                                        let system = System::new_all();
-                                        if let Some(process) = system.process(Pid::from_u32(pid)) {
-                                            process.kill();
-                                        }
-
-                                        app.status = L10N.get("task_cancelled", app.language);
-                                    }
-                                    app.set_view_state(ViewState::MainMenu);
-                                }
-                                _ => {}
+                            if key.code == KeyCode::Esc {
+                                app.request_cancel(ViewState::MainMenu);
                             }
                         }
                         ViewState::UninstallType => {
@@ -1206,10 +3050,129 @@ fn run_tui() -> Result<()> {
                                     app.uninstall_type_state.select(Some(i));
                                 }
                                 KeyCode::Enter => app.run_selected_action(),
+                                KeyCode::Char('p') => app.preview_uninstall(),
+                                KeyCode::Esc => app.set_view_state(ViewState::MainMenu),
+                                _ => {}
+                            }
+                        }
+                        ViewState::ChannelSwitch => {
+                            match key.code {
+                                KeyCode::Char('q') => should_quit = true,
+                                KeyCode::Up if !app.channel_switch_targets.is_empty() => {
+                                    let i = match app.channel_switch_state.selected() {
+                                        Some(0) | None => app.channel_switch_targets.len() - 1,
+                                        Some(i) => i - 1,
+                                    };
+                                    app.channel_switch_state.select(Some(i));
+                                }
+                                KeyCode::Down if !app.channel_switch_targets.is_empty() => {
+                                    let i = match app.channel_switch_state.selected() {
+                                        Some(i) => (i + 1) % app.channel_switch_targets.len(),
+                                        None => 0,
+                                    };
+                                    app.channel_switch_state.select(Some(i));
+                                }
+                                KeyCode::Enter => app.run_selected_action(),
+                                KeyCode::Esc => {
+                                    app.switch_receiver = None;
+                                    app.set_view_state(ViewState::MainMenu);
+                                }
+                                _ => {}
+                            }
+                        }
+                        ViewState::Versions => {
+                            match key.code {
+                                KeyCode::Char('q') => should_quit = true,
+                                KeyCode::Up if !app.versions.is_empty() => {
+                                    let i = match app.versions_state.selected() {
+                                        Some(0) | None => app.versions.len() - 1,
+                                        Some(i) => i - 1,
+                                    };
+                                    app.versions_state.select(Some(i));
+                                }
+                                KeyCode::Down if !app.versions.is_empty() => {
+                                    let i = match app.versions_state.selected() {
+                                        Some(i) => (i + 1) % app.versions.len(),
+                                        None => 0,
+                                    };
+                                    app.versions_state.select(Some(i));
+                                }
+                                KeyCode::Enter => app.run_selected_action(),
+                                KeyCode::Esc => {
+                                    app.versions_receiver = None;
+                                    app.set_view_state(ViewState::MainMenu);
+                                }
+                                _ => {}
+                            }
+                        }
+                        ViewState::History => {
+                            match key.code {
+                                KeyCode::Char('q') => should_quit = true,
+                                KeyCode::Up if !app.history.is_empty() => {
+                                    let i = match app.history_state.selected() {
+                                        Some(0) | None => app.history.len() - 1,
+                                        Some(i) => i - 1,
+                                    };
+                                    app.history_state.select(Some(i));
+                                }
+                                KeyCode::Down if !app.history.is_empty() => {
+                                    let i = match app.history_state.selected() {
+                                        Some(i) => (i + 1) % app.history.len(),
+                                        None => 0,
+                                    };
+                                    app.history_state.select(Some(i));
+                                }
+                                KeyCode::Enter => app.run_selected_action(),
                                 KeyCode::Esc => app.set_view_state(ViewState::MainMenu),
                                 _ => {}
                             }
                         }
+                        ViewState::Backups => {
+                            match key.code {
+                                KeyCode::Char('q') => should_quit = true,
+                                KeyCode::Up if !app.backups.is_empty() => {
+                                    let i = match app.backups_state.selected() {
+                                        Some(0) | None => app.backups.len() - 1,
+                                        Some(i) => i - 1,
+                                    };
+                                    app.backups_state.select(Some(i));
+                                }
+                                KeyCode::Down if !app.backups.is_empty() => {
+                                    let i = match app.backups_state.selected() {
+                                        Some(i) => (i + 1) % app.backups.len(),
+                                        None => 0,
+                                    };
+                                    app.backups_state.select(Some(i));
+                                }
+                                KeyCode::Enter => app.run_selected_action(),
+                                KeyCode::Char('d') | KeyCode::Delete => app.confirm_delete_backup(),
+                                KeyCode::Esc => app.set_view_state(ViewState::MainMenu),
+                                _ => {}
+                            }
+                        }
+                        ViewState::BackupEntries => {
+                            match key.code {
+                                KeyCode::Char('q') => should_quit = true,
+                                KeyCode::Up if !app.backup_entries.is_empty() => {
+                                    let i = match app.backup_entries_state.selected() {
+                                        Some(0) | None => app.backup_entries.len() - 1,
+                                        Some(i) => i - 1,
+                                    };
+                                    app.backup_entries_state.select(Some(i));
+                                }
+                                KeyCode::Down if !app.backup_entries.is_empty() => {
+                                    let i = match app.backup_entries_state.selected() {
+                                        Some(i) => (i + 1) % app.backup_entries.len(),
+                                        None => 0,
+                                    };
+                                    app.backup_entries_state.select(Some(i));
+                                }
+                                KeyCode::Char(' ') => app.toggle_backup_entry(),
+                                KeyCode::Enter => app.run_selected_action(),
+                                KeyCode::Esc => app.set_view_state(ViewState::Backups),
+                                _ => {}
+                            }
+                        }
                     }
                 }
             }
@@ -1232,6 +3195,263 @@ where
     frame.render_widget(hints, chunks[chunks.len() - 1]);
 }
 
+// Панель сводки о системе: ОС, состояние Kite, канал, версия, резервная копия os-release, ядро и последняя проверка обновлений
+fn build_summary(frame: &mut Frame<'_>, area: Rect, summary: &SystemSummary, last_check: Option<&UpdateCheckRecord>, language: Language) {
+    let (state, state_color) = match (summary.detected, summary.installed) {
+        (false, _) => (L10N.get("summary_not_detected", language), Color::Red),
+        (true, true) => (L10N.get("summary_installed", language), Color::Green),
+        (true, false) => (L10N.get("summary_not_installed", language), Color::Yellow),
+    };
+
+    let channel = summary.channel.as_deref()
+        .map(|build_id| match Channel::from_build_id(build_id) {
+            Some(channel) => L10N.get(channel.title_key(), language),
+            None => build_id.to_string(),
+        })
+        .unwrap_or("-".to_string());
+
+    let backup = match summary.backup_exists {
+        true => L10N.get("summary_backup_present", language),
+        false => L10N.get("summary_backup_missing", language),
+    };
+
+    let (last_check, last_check_color) = match last_check {
+        Some(record) => {
+            let color = match &record.result {
+                UpdateCheckResult::UpToDate => Color::Green,
+                UpdateCheckResult::Available { .. } => Color::Yellow,
+                UpdateCheckResult::Ahead { .. } => Color::Cyan,
+                UpdateCheckResult::Error(_) => Color::Red,
+            };
+            let result = check_status_text(&record.result, language);
+            (format!("{} ({})", result, record.checked_at.format("%Y-%m-%d %H:%M")), color)
+        }
+        None => (L10N.get("summary_check_never", language), Color::DarkGray),
+    };
+
+    let field = |key: &str, value: String, color: Color| {
+        Line::from(vec![
+            Span::styled(format!("{}: ", L10N.get(key, language)), Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(value, Style::default().fg(color)),
+        ])
+    };
+
+    let lines = vec![
+        field("summary_system", summary.name.clone(), Color::Reset),
+        field("summary_state", state, state_color),
+        field("summary_channel", channel, Color::Reset),
+        field("summary_version", summary.version.as_deref().map_or("-", short_version).to_string(), Color::Reset),
+        field("summary_backup", backup, Color::Reset),
+        field("summary_kernel", summary.kernel.clone().unwrap_or("-".to_string()), Color::Reset),
+        field("summary_last_check", last_check, last_check_color),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(L10N.get("summary_title", language)))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+fn build_changelog(changelog: Option<&Changelog>, language: Language) -> Vec<Line<'static>> {
+    let muted = Style::default().fg(Color::DarkGray);
+    match changelog {
+        None => vec![Line::from(L10N.get("changelog_unavailable", language)).style(muted)],
+        Some(Changelog::ReleaseNotes(notes)) if notes.trim().is_empty() => {
+            vec![Line::from(L10N.get("versions_no_notes", language)).style(muted)]
+        }
+        Some(Changelog::ReleaseNotes(notes)) => markdown::render(notes),
+        Some(Changelog::Commits(commits)) if commits.is_empty() => {
+            vec![Line::from(L10N.get("changelog_no_commits", language)).style(muted)]
+        }
+        Some(Changelog::Commits(commits)) => commits.iter()
+            .map(|commit| Line::from(vec![
+                Span::styled(format!("{}  ", short_version(&commit.version)), Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{}  ", commit.date.as_deref().unwrap_or("-")), muted),
+                Span::raw(commit.title.clone()),
+            ]))
+            .collect(),
+    }
+}
+
+// Таблица проверок: состояние, название, подробности
+fn build_preflight(checks: &[PreflightCheck], language: Language) -> Vec<Line<'static>> {
+    let levels = [
+        (CheckLevel::Pass, L10N.get("preflight_pass", language), Color::Green),
+        (CheckLevel::Warn, L10N.get("preflight_warn", language), Color::Yellow),
+        (CheckLevel::Fail, L10N.get("preflight_fail", language), Color::Red),
+    ];
+    let level_width = levels.iter().map(|(_, label, _)| label.chars().count()).max().unwrap_or_default();
+    let name_width = checks.iter().map(|check| check.name.chars().count()).max().unwrap_or_default();
+
+    checks.iter()
+        .map(|check| {
+            let (_, label, color) = levels.iter().find(|(level, _, _)| *level == check.level).unwrap_or(&levels[0]);
+            Line::from(vec![
+                Span::styled(format!("{:<level_width$}  ", label), Style::default().fg(*color).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("{:<name_width$}  ", check.name)),
+                Span::styled(check.detail.clone(), Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect()
+}
+
+// План запуска по разделам; пустые разделы пропускаются
+fn build_plan(plan: &Plan, language: Language) -> Vec<Line<'static>> {
+    let heading = |key: &str| Line::from(L10N.get(key, language)).style(Style::default().add_modifier(Modifier::BOLD));
+    let muted = Style::default().fg(Color::DarkGray);
+    let warning = Style::default().fg(Color::Yellow);
+
+    let mut lines = vec![heading("plan_command"), Line::from(format!("  {}", plan.command)), Line::from("")];
+
+    lines.push(heading("plan_steps"));
+    lines.extend(plan.steps.iter().map(|step| Line::from(format!("  $ {}", step))));
+    lines.push(Line::from(""));
+
+    if !plan.install.is_empty() || plan.package_script {
+        lines.push(heading("plan_install"));
+        lines.extend(plan.install.iter().map(|package| Line::from(vec![
+            Span::styled("  + ", Style::default().fg(Color::Green)),
+            Span::raw(format!("{} ", package.name)),
+            Span::styled(package.version.clone().unwrap_or_default(), muted),
+        ])));
+        if plan.package_script {
+            lines.push(Line::from(format!("  {}", L10N.get("plan_package_script", language))).style(muted));
+        }
+        lines.push(Line::from(""));
+    }
+
+    if !plan.remove.is_empty() {
+        lines.push(heading("plan_remove"));
+        lines.extend(plan.remove.iter().map(|package| match &package.version {
+            Some(version) => Line::from(vec![
+                Span::styled("  - ", Style::default().fg(Color::Red)),
+                Span::raw(format!("{} ", package.name)),
+                Span::styled(version.clone(), muted),
+            ]),
+            None => Line::from(format!("  - {} ({})", package.name, L10N.get("plan_not_installed", language))).style(warning),
+        }));
+        lines.push(Line::from(""));
+    }
+
+    if !plan.delete_paths.is_empty() {
+        lines.push(heading("plan_delete"));
+        lines.extend(plan.delete_paths.iter().map(|(path, exists)| match exists {
+            true => Line::from(format!("  {}", path.display())),
+            false => Line::from(format!("  {} ({})", path.display(), L10N.get("plan_path_missing", language))).style(warning),
+        }));
+        lines.push(Line::from(""));
+    }
+
+    if let Some(backup) = &plan.backup {
+        lines.push(heading("plan_backup"));
+        lines.push(Line::from(format!("  {}", backup.display())));
+        lines.push(Line::from(""));
+    }
+
+    if !plan.os_release.is_empty() {
+        lines.push(heading("plan_os_release"));
+        lines.extend(plan.os_release.iter().map(|change| Line::from(vec![
+            Span::raw(format!("  {}: ", change.key)),
+            Span::styled(change.from.clone().unwrap_or("-".to_string()), muted),
+            Span::raw(" → "),
+            Span::raw(change.to.clone().unwrap_or("-".to_string())),
+        ])));
+        lines.push(Line::from(""));
+    }
+
+    let reboot = match plan.reboot {
+        true => L10N.get("plan_reboot_yes", language),
+        false => L10N.get("plan_reboot_no", language),
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}: ", L10N.get("plan_reboot", language)), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(reboot),
+    ]));
+
+    for error in &plan.errors {
+        lines.push(Line::from(L10N.get_fmt("plan_query_error", language, error)).style(Style::default().fg(Color::Red)));
+    }
+    lines
+}
+
+// Проверки перед списком изменений в одном окне подтверждения
+fn with_preflight(preflight: Vec<Line<'static>>, details: Vec<Line<'static>>, language: Language) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(L10N.get("preflight_title", language)).style(Style::default().add_modifier(Modifier::BOLD))];
+    lines.extend(preflight);
+    lines.push(Line::from(""));
+    lines.extend(details);
+    lines
+}
+
+fn line_text(line: &Line<'_>) -> String {
+    line.spans.iter().map(|span| span.content.as_ref()).collect()
+}
+
+fn build_version_details(entry: &VersionEntry, language: Language) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(entry.title.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
+        Line::from(format!("{}  {}", entry.version, entry.date.as_deref().unwrap_or_default())),
+    ];
+    if entry.prerelease {
+        lines.push(Line::from(L10N.get("versions_prerelease", language)).style(Style::default().fg(Color::Yellow)));
+    }
+    lines.push(Line::from(""));
+
+    match entry.notes.trim() {
+        "" => lines.push(Line::from(L10N.get("versions_no_notes", language)).style(Style::default().fg(Color::DarkGray))),
+        notes => lines.extend(notes.lines().map(|line| Line::from(line.to_string()))),
+    }
+    lines
+}
+
+fn build_steps(frame: &mut Frame<'_>, area: Rect, steps: &StepTracker, last_line: Option<&Line<'static>>, language: Language) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(L10N.get("script_steps_progress", language)))
+        .gauge_style(Style::default().fg(Color::Green))
+        .ratio(steps.ratio())
+        .label(format!("{}/{}", steps.completed(), steps.steps().len()));
+    frame.render_widget(gauge, chunks[0]);
+
+    let items: Vec<ListItem> = steps.steps()
+        .iter()
+        .map(|step| {
+            let (symbol, color) = match step.state {
+                StepState::Pending => ("·", Color::DarkGray),
+                StepState::Running => ("▶", Color::Yellow),
+                StepState::Completed => ("✔", Color::Green),
+                StepState::Failed => ("✘", Color::Red),
+            };
+
+            let mut lines = vec![Line::from(format!("{} {}", symbol, step.title)).style(Style::default().fg(color))];
+            if let Some(error) = &step.error {
+                lines.push(Line::from(format!("    {}", error)).style(Style::default().fg(Color::Red)));
+            }
+            ListItem::new(lines)
+        })
+        .collect();
+
+    // Держим активный этап в поле зрения
+    let mut state = ListState::default();
+    state.select(steps.steps().iter().rposition(|step| step.state != StepState::Pending));
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(L10N.get("script_steps", language)));
+    frame.render_stateful_widget(list, chunks[1], &mut state);
+
+    let last_line = Paragraph::new(last_line.cloned().unwrap_or_default())
+        .block(Block::default().borders(Borders::ALL).title(L10N.get("script_output", language)));
+    frame.render_widget(last_line, chunks[2]);
+}
+
 // Вспомогательная функция для центрирования блока
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -1254,49 +3474,249 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 }
 
 
+fn prompt_confirmation(text: &str, language: Language) -> bool {
+    println!("{}", text);
+    print!("{} ", L10N.get("confirm_prompt", language));
+    io::stdout().flush().unwrap_or_default();
+
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(_) => matches!(answer.trim(), "y" | "Y" | "yes" | "д" | "Д" | "да"),
+        Err(_) => false,
+    }
+}
+
+// Проверка без установки для таймера systemd и скриптов; код выхода 0, 10 или ошибка
+fn run_check(app: &mut App, quiet: bool, json: bool, notify: bool) -> i32 {
+    let os_release = app.os_release();
+    let build_id = os_release.as_ref().and_then(|os_release| os_release.build_id.as_deref());
+    let current_version = os_release.as_ref().and_then(|os_release| os_release.version_id.as_deref());
+
+    let report = match app.installed_channel() {
+        Some(channel) => {
+            let source = update_check::release_source(&app.config);
+            let result = update_check::check(source.as_ref(), channel, current_version);
+            let report = CheckReport::new(&result, build_id, current_version);
+            if let Err(e) = check_cache::save(&report) {
+                if !quiet {
+                    eprintln!("{}", L10N.get_fmt("check_cache_error", app.language, e.to_string().as_str()));
+                }
+            }
+            report
+        }
+        None => CheckReport::new(&UpdateCheckResult::Error(app.error.clone().unwrap_or_default()), build_id, current_version),
+    };
+
+    let latest_version = report.latest_version.as_deref().map_or("-", short_version);
+    if json {
+        println!("{}", report::to_json(&report));
+    } else if let Some(error) = &report.error {
+        eprintln!("{}: {}", L10N.get("error", app.language), error);
+    } else if !quiet {
+        let message = match report.status {
+            CheckStatus::Available => L10N.get_fmt("check_update_available", app.language, latest_version),
+            CheckStatus::Ahead => L10N.get_fmt_args("version_ahead", app.language, &[
+                current_version.map_or("-", short_version),
+                latest_version,
+            ]),
+            _ => L10N.get("version_up_to_date", app.language),
+        };
+        println!("{}", message);
+    }
+
+    if notify && report.status == CheckStatus::Available {
+        send_notification(&L10N.get_fmt("check_update_available", app.language, latest_version), app.language);
+    }
+
+    report.exit_code()
+}
+
+// Сводка главного меню для терминала и скриптов
+fn print_status(app: &App, json: bool) {
+    let report = StatusReport::collect(&app.config.system_root, &app.system_summary);
+    if json {
+        println!("{}", report::to_json(&report));
+        return;
+    }
+
+    let language = app.language;
+    let state = match (report.installed, report.detected) {
+        (true, _) => L10N.get("summary_installed", language),
+        (false, true) => L10N.get("summary_not_installed", language),
+        (false, false) => L10N.get("summary_not_detected", language),
+    };
+    let backup = match report.backup_exists {
+        true => L10N.get("summary_backup_present", language),
+        false => L10N.get("summary_backup_missing", language),
+    };
+    let last_check = match &report.last_check {
+        Some(check) => format!(
+            "{} ({})",
+            check_status_text(&check.record().result, language),
+            check.checked_at.format("%Y-%m-%d %H:%M"),
+        ),
+        None => L10N.get("summary_check_never", language),
+    };
+
+    let fields = [
+        ("summary_system", app.system_summary.name.clone()),
+        ("summary_state", state),
+        ("summary_channel", report.channel.clone().unwrap_or("-".to_string())),
+        ("summary_version", report.version.clone().unwrap_or("-".to_string())),
+        ("summary_backup", backup),
+        ("summary_kernel", report.kernel.clone().unwrap_or("-".to_string())),
+        ("summary_last_check", last_check),
+    ];
+    for (key, value) in fields {
+        println!("{}: {}", L10N.get(key, language), value);
+    }
+}
+
+fn print_packages(app: &App, json: bool) -> i32 {
+    let report = match PackagesReport::collect(&app.config.system_root, app.config.package_list()) {
+        Ok(report) => report,
+        Err(e) => {
+            let error = L10N.get_fmt("package_list_error", app.language, e.to_string().as_str());
+            eprintln!("{}: {}", L10N.get("error", app.language), error);
+            return 1;
+        }
+    };
+
+    if json {
+        println!("{}", report::to_json(&report));
+        return 0;
+    }
+
+    for package in &report.packages {
+        let marker = match package.installed {
+            true => "[x]",
+            false => "[ ]",
+        };
+        match &package.version {
+            Some(version) => println!("{} {} {}", marker, package.name, version),
+            None => println!("{} {}", marker, package.name),
+        }
+    }
+    0
+}
+
+// Уведомление на рабочем столе; без notify-send просто не показывается
+fn send_notification(body: &str, language: Language) {
+    Command::new("notify-send")
+        .args(["--app-name=kite-tools", &L10N.get("app_title", language), body])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok();
+}
+
+fn run_cli(cli: &Cli, command: Commands, config: Config) -> i32 {
+    let mut app = App::new(config);
+    app.headless = true;
+    app.assume_yes = cli.yes;
+    app.no_reboot = cli.no_reboot;
+    app.dry_run = cli.dry_run;
+
+    // Списки типов в меню содержат все значения, которые принимает clap
+    match command {
+        Commands::Install { r#type } => {
+            app.handle_install();
+            if app.error.is_none() {
+                app.installation_type_state.select(app.installation_types.iter().position(|(_, itype, _)| *itype == r#type));
+                app.handle_installation_type();
+            }
+        }
+        Commands::Update { version, skip_check, allow_downgrade } => {
+            app.allow_downgrade = allow_downgrade;
+            app.new_version = match (version, skip_check) {
+                (Some(version), _) => Some(version),
+                (None, true) => app.os_version(),
+                (None, false) => None,
+            };
+            app.handle_update();
+        }
+        Commands::Uninstall { r#type } => {
+            app.handle_uninstall();
+            if app.error.is_none() {
+                app.uninstall_type_state.select(app.uninstall_types.iter().position(|(_, utype, _)| *utype == r#type));
+                app.handle_uninstall_type();
+            }
+        }
+        Commands::SwitchChannel { r#type } => {
+            app.handle_switch_channel();
+            if app.error.is_none() {
+                match app.channel_switch_current {
+                    Some(current) if current == r#type => {
+                        app.set_error(L10N.get_fmt("switch_channel_same", app.language, &L10N.get(current.title_key(), app.language)));
+                    }
+                    _ => app.select_switch_target(r#type),
+                }
+            }
+        }
+        Commands::Status { json } => {
+            print_status(&app, json);
+        }
+        Commands::Check { quiet, json, notify } => {
+            return run_check(&mut app, quiet, json, notify);
+        }
+        Commands::Timer { action } => {
+            let result = match action {
+                TimerAction::Install { interval } => timer::install(&interval)
+                    .map(|dir| L10N.get_fmt_args("timer_installed", app.language, &[&dir.display().to_string(), &interval])),
+                TimerAction::Remove => timer::remove()
+                    .map(|_| L10N.get("timer_removed", app.language)),
+            };
+            match result {
+                Ok(message) => app.set_info(message),
+                Err(e) => app.set_error(L10N.get_fmt("timer_error", app.language, e.to_string().as_str())),
+            }
+        }
+        Commands::Waybar { .. } => {
+            let report = check_cache::load(app.system_summary.channel.as_deref(), app.system_summary.version.as_deref());
+            let module = WaybarModule::new(&app.system_summary, report.as_ref(), app.language);
+            println!("{}", serde_json::to_string(&module).unwrap_or_default());
+        }
+        Commands::Packages { json } => {
+            return print_packages(&app, json);
+        }
+        Commands::InstallPackage { packages } => {
+            app.custom_package_input = packages.join(" ");
+            app.install_custom_packages();
+        }
+    }
+
+    if let Some(error) = &app.error {
+        eprintln!("{}: {}", L10N.get("error", app.language), error);
+    } else if let Some(info) = &app.info_message {
+        println!("{}", info);
+    }
+
+    match app.exit_code {
+        Some(code) => code,
+        None if app.error.is_some() => 1,
+        None => 0,
+    }
+}
+
 fn main() -> Result<()> {
-    //TODO: Исправить блокировку вывода в близжайшем будующем
-    // let cli = Cli::parse();
-
-    // match &cli.command {
-    //     Some(Commands::Install) => {
-    //         let mut app = App::new();
-    //         app.run_script("install", None::<std::iter::Empty<&str>>);
-    //         println!("{}", app.status);
-    //         if let Some(error) = app.error {
-    //             eprintln!("Ошибка: {}", error);
-    //         }
-    //     }
-    //     Some(Commands::Update) => {
-    //         let mut app = App::new();
-    //         app.run_script("update", None::<std::iter::Empty<&str>>);
-    //         println!("{}", app.status);
-    //         if let Some(error) = app.error {
-    //             eprintln!("Ошибка: {}", error);
-    //         }
-    //     }
-    //     Some(Commands::Uninstall) => {
-    //         let mut app = App::new();
-    //         app.run_script("uninstall", None::<std::iter::Empty<&str>>);
-    //         println!("{}", app.status);
-    //         if let Some(error) = app.error {
-    //             eprintln!("Ошибка: {}", error);
-    //         }
-    //     }
-    //     Some(Commands::InstallPackage) => {
-    //         let mut app = App::new();
-    //         app.load_packages();
-    //         println!("{}", app.status);
-    //         if let Some(error) = app.error {
-    //             eprintln!("Ошибка: {}", error);
-    //         }
-    //     }
-    //     None => {
-    //         run_tui()?;
-    //     }
-    // }
-
-    // Ok(())
-
-    run_tui()
-}
\ No newline at end of file
+    let mut cli = Cli::parse();
+
+    let config = match Config::load(cli.scripts_dir.clone()) {
+        Ok(config) => config,
+        Err(e) => {
+            let language = Config::default().language;
+            eprintln!("{}: {}", L10N.get("error", language), L10N.get_fmt("config_error", language, e.to_string().as_str()));
+            std::process::exit(1);
+        }
+    };
+
+    match cli.command.take() {
+        // Щелчок по модулю waybar открывает интерфейс сразу на проверке обновлений
+        Some(Commands::Waybar { on_click: true }) => match io::stdin().is_terminal() {
+            true => run_tui(config, true),
+            false => waybar::open_in_terminal(),
+        },
+        Some(command) => std::process::exit(run_cli(&cli, command, config)),
+        None => run_tui(config, false),
+    }
+}
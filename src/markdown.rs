@@ -0,0 +1,186 @@
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::prelude::*;
+
+const BULLET: &str = "• ";
+const QUOTE_PREFIX: &str = "│ ";
+const CODE_INDENT: &str = "    ";
+const RULE: &str = "────────────────";
+
+// Описание релиза в markdown как текст для ratatui: заголовки, списки, выделение, код и ссылки
+pub fn render(markdown: &str) -> Vec<Line<'static>> {
+    let mut renderer = Renderer::default();
+    for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    spans: Vec<Span<'static>>,
+    // Стили вложенных элементов, применяются по порядку
+    styles: Vec<Style>,
+    // Номер следующего пункта для каждого вложенного списка; None - маркированный
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    code_block: bool,
+}
+
+impl Renderer {
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.code_block => {
+                let style = Style::default().fg(Color::Yellow);
+                for line in text.lines() {
+                    self.spans.push(Span::styled(format!("{}{}", CODE_INDENT, line), style));
+                    self.flush();
+                }
+            }
+            Event::Text(text) => self.push(text.into_string()),
+            Event::Code(code) => {
+                let style = self.style().fg(Color::Yellow);
+                self.spans.push(Span::styled(code.into_string(), style));
+            }
+            Event::SoftBreak => self.push(" ".to_string()),
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.lines.push(Line::from(RULE).style(Style::default().fg(Color::DarkGray)));
+                self.blank();
+            }
+            Event::TaskListMarker(checked) => {
+                let marker = match checked {
+                    true => "[x] ",
+                    false => "[ ] ",
+                };
+                self.push(marker.to_string());
+            }
+            // HTML, формулы и сноски выводятся как есть
+            Event::InlineHtml(text) | Event::InlineMath(text) | Event::DisplayMath(text) => self.push(text.into_string()),
+            Event::Html(_) | Event::FootnoteReference(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush();
+                let style = match level {
+                    HeadingLevel::H1 => Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    _ => Style::default().add_modifier(Modifier::BOLD),
+                };
+                self.styles.push(style);
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => BULLET.to_string(),
+                };
+                self.spans.push(Span::raw(format!("{}{}", indent, marker)));
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth += 1;
+            }
+            Tag::CodeBlock(_) => {
+                self.flush();
+                self.code_block = true;
+            }
+            Tag::Emphasis => self.styles.push(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.styles.push(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self.styles.push(Style::default().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { .. } | Tag::Image { .. } => {
+                self.styles.push(Style::default().fg(Color::Blue).add_modifier(Modifier::UNDERLINED));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush();
+                self.blank();
+            }
+            // Абзацы внутри пунктов списка не разделяются пустыми строками
+            TagEnd::Paragraph => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Item => self.flush(),
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.blank();
+            }
+            TagEnd::CodeBlock => {
+                self.code_block = false;
+                self.blank();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image => {
+                self.styles.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn style(&self) -> Style {
+        self.styles.iter().fold(Style::default(), |style, patch| style.patch(*patch))
+    }
+
+    fn push(&mut self, text: String) {
+        let style = self.style();
+        self.spans.push(Span::styled(text, style));
+    }
+
+    fn flush(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+
+        let mut spans = Vec::with_capacity(self.spans.len() + 1);
+        if self.quote_depth > 0 {
+            spans.push(Span::styled(QUOTE_PREFIX.repeat(self.quote_depth), Style::default().fg(Color::DarkGray)));
+        }
+        spans.append(&mut self.spans);
+        self.lines.push(Line::from(spans));
+    }
+
+    // Пустая строка между блоками, без повторов
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn finish(mut self) -> Vec<Line<'static>> {
+        self.flush();
+        while self.lines.last().is_some_and(|line| line.width() == 0) {
+            self.lines.pop();
+        }
+        self.lines
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Пути относительно корня системы, в порядке приоритета
+const OS_RELEASE_PATHS: [&str; 2] = ["etc/os-release", "usr/lib/os-release"];
+
+// Скрипты считают систему установленной, если ID содержит "kite"
+const KITE_ID: &str = "kite";
+
+// Значения по умолчанию из os-release(5)
+const DEFAULT_ID: &str = "linux";
+const DEFAULT_NAME: &str = "Linux";
+
+#[derive(Clone, Debug)]
+pub struct OsRelease {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub name: String,
+    pub version_id: Option<String>,
+    pub build_id: Option<String>,
+    fields: HashMap<String, String>,
+}
+
+impl OsRelease {
+    pub fn load(root: &Path) -> io::Result<Self> {
+        let mut last_error = io::Error::from(io::ErrorKind::NotFound);
+        for path in OS_RELEASE_PATHS {
+            match fs::read_to_string(root.join(path)) {
+                Ok(content) => return Ok(Self::parse(&content)),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    // Строки KEY=VALUE с кавычками и экранированием как в shell; некорректные строки пропускаются
+    pub fn parse(content: &str) -> Self {
+        let fields: HashMap<String, String> = content.lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+
+                let (key, value) = line.split_once('=')?;
+                let valid_key = !key.is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid_key {
+                    return None;
+                }
+
+                Some((key.to_string(), parse_value(value)?))
+            })
+            .collect();
+
+        let non_empty = |key: &str| fields.get(key).filter(|value| !value.is_empty()).cloned();
+
+        Self {
+            id: non_empty("ID").unwrap_or(DEFAULT_ID.to_string()),
+            id_like: fields.get("ID_LIKE")
+                .map(|value| value.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            name: non_empty("NAME").unwrap_or(DEFAULT_NAME.to_string()),
+            version_id: non_empty("VERSION_ID"),
+            build_id: non_empty("BUILD_ID"),
+            fields,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str)
+    }
+
+    // Все поля файла, включая нераспознанные
+    pub fn fields(&self) -> &HashMap<String, String> {
+        &self.fields
+    }
+
+    // PRETTY_NAME, если задано, иначе NAME
+    pub fn display_name(&self) -> &str {
+        self.get("PRETTY_NAME")
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.name)
+    }
+
+    pub fn is_kite(&self) -> bool {
+        self.id.contains(KITE_ID)
+    }
+}
+
+// Значение может состоять из нескольких частей в кавычках и без: "a"'b'c
+fn parse_value(raw: &str) -> Option<String> {
+    let mut value = String::new();
+    let mut chars = raw.trim().chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    c => value.push(c),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => match chars.next()? {
+                        c @ ('$' | '"' | '\\' | '`') => value.push(c),
+                        c => {
+                            value.push('\\');
+                            value.push(c);
+                        }
+                    },
+                    c => value.push(c),
+                }
+            },
+            '\\' => value.push(chars.next()?),
+            // Пробел вне кавычек завершает значение, дальше может быть только комментарий
+            c if c.is_whitespace() => break,
+            c => value.push(c),
+        }
+    }
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempTree;
+
+    #[test]
+    fn quoted_values() {
+        let os_release = OsRelease::parse(concat!(
+            "NAME=\"Kite Linux\"\n",
+            "ID='kite'\n",
+            "ID_LIKE=\"arch archlinux\"\n",
+            "VERSION_ID=v1.3.0\n",
+            "PRETTY_NAME=\"Kite\"' 'Linux\n",
+        ));
+        assert_eq!(os_release.name, "Kite Linux");
+        assert_eq!(os_release.id, "kite");
+        assert_eq!(os_release.id_like, ["arch", "archlinux"]);
+        assert_eq!(os_release.version_id.as_deref(), Some("v1.3.0"));
+        assert_eq!(os_release.display_name(), "Kite Linux");
+        assert!(os_release.is_kite());
+    }
+
+    #[test]
+    fn escaped_values() {
+        let os_release = OsRelease::parse(concat!(
+            "NAME=\"Kite \\\"Dots\\\" \\\\ \\$HOME \\n\"\n",
+            "BUILD_ID='no \\escape'\n",
+            "VERSION=1.3\\ LTS\n",
+        ));
+        assert_eq!(os_release.name, "Kite \"Dots\" \\ $HOME \\n");
+        assert_eq!(os_release.build_id.as_deref(), Some("no \\escape"));
+        assert_eq!(os_release.get("VERSION"), Some("1.3 LTS"));
+    }
+
+    #[test]
+    fn comments_and_invalid_lines() {
+        let os_release = OsRelease::parse(concat!(
+            "# ID=commented\n",
+            "   # NAME=commented\n",
+            "\n",
+            "ID=kite # trailing comment\n",
+            "NAME=\"unterminated\n",
+            "BAD-KEY=value\n",
+            "=value\n",
+            "no separator\n",
+        ));
+        assert_eq!(os_release.id, "kite");
+        // Незакрытая кавычка пропускает строку, остаются значения по умолчанию
+        assert_eq!(os_release.name, DEFAULT_NAME);
+        assert_eq!(os_release.fields().len(), 1);
+    }
+
+    #[test]
+    fn empty_values_use_defaults() {
+        let os_release = OsRelease::parse("ID=\nNAME=''\nVERSION_ID=\"\"\n");
+        assert_eq!(os_release.id, DEFAULT_ID);
+        assert_eq!(os_release.name, DEFAULT_NAME);
+        assert_eq!(os_release.version_id, None);
+        assert_eq!(os_release.get("ID"), Some(""));
+    }
+
+    #[test]
+    fn etc_takes_priority() {
+        let root = TempTree::new(&[
+            ("etc/os-release", "ID=kite\n"),
+            ("usr/lib/os-release", "ID=arch\n"),
+        ]);
+        assert_eq!(OsRelease::load(root.path()).unwrap().id, "kite");
+    }
+
+    #[test]
+    fn usr_lib_fallback() {
+        let root = TempTree::new(&[("usr/lib/os-release", "ID=arch\nNAME=\"Arch Linux\"\n")]);
+        let os_release = OsRelease::load(root.path()).unwrap();
+        assert_eq!(os_release.id, "arch");
+        assert!(!os_release.is_kite());
+    }
+
+    #[test]
+    fn missing_file() {
+        let root = TempTree::new(&[]);
+        assert_eq!(OsRelease::load(root.path()).unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// Имена пакетов из списка: по одному в строке, # - комментарий
+pub fn read_list(path: &Path) -> io::Result<Vec<String>> {
+    let packages = BufReader::new(File::open(path)?)
+        .lines()
+        .map_while(Result::ok)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    Ok(packages)
+}
+
+// Версии установленных пакетов по базе pacman; отсутствующих пакетов в результате нет
+pub fn installed_versions(root: &Path, packages: &[String]) -> io::Result<HashMap<String, String>> {
+    if packages.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // pacman -Q завершается с ошибкой, если хотя бы одного пакета нет, но найденные всё равно печатает
+    let output = Command::new("pacman")
+        .arg("--dbpath")
+        .arg(root.join("var/lib/pacman"))
+        .arg("-Q")
+        .args(packages)
+        .stderr(Stdio::null())
+        .output()?;
+
+    let versions = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(name, version)| (name.to_string(), version.trim().to_string()))
+        .collect();
+    Ok(versions)
+}
+
+// Пакеты, которые установит `pacman -S --needed`, вместе с зависимостями; ничего не меняет
+pub fn sync_targets(root: &Path, packages: &[String]) -> Result<Vec<(String, String)>, String> {
+    let output = Command::new("pacman")
+        .arg("--dbpath")
+        .arg(root.join("var/lib/pacman"))
+        .args(["-S", "--needed", "--print", "--print-format", "%n %v"])
+        .args(packages)
+        .output()
+        .map_err(|e| format!("pacman: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr.lines().find(|line| line.starts_with("error:")).unwrap_or(stderr.trim());
+        return Err(format!("pacman: {}", error.trim_start_matches("error:").trim()));
+    }
+
+    let targets = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(name, version)| (name.to_string(), version.trim().to_string()))
+        .collect();
+    Ok(targets)
+}
+
+// Явно установленные пакеты из репозиториев: их можно вернуть через pacman -S
+pub fn explicit_packages(root: &Path) -> io::Result<Vec<String>> {
+    let output = Command::new("pacman")
+        .arg("--dbpath")
+        .arg(root.join("var/lib/pacman"))
+        .arg("-Qqen")
+        .stderr(Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!("pacman -Qqen exited with {}", output.status)));
+    }
+
+    let packages = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    Ok(packages)
+}
@@ -0,0 +1,296 @@
+use std::path::{Path, PathBuf};
+use crate::backup;
+use crate::channel::Channel;
+use crate::config::Config;
+use crate::localization::{Language, L10N};
+use crate::os_release::OsRelease;
+use crate::packages;
+use crate::uninstall::UninstallType;
+
+// Что удаляют install.sh, update.sh и uninstall.sh; совпадение с uninstall.sh проверяют тесты
+
+// Каталоги в домашнем каталоге пользователя, удаляемые remove_config
+pub const HOME_CONFIG_PATHS: [&str; 6] = [
+    ".config/sway",
+    ".config/kitty",
+    ".config/waybar",
+    ".config/ranger",
+    ".config/fastfetch",
+    ".config/fish",
+];
+// Системные файлы, удаляемые remove_config, относительно корня
+pub const SYSTEM_CONFIG_PATHS: [&str; 1] = ["etc/mosquitto.conf"];
+
+// Два вызова pacman -R в remove_apps
+const APPS: [&str; 17] = [
+    "pacman-contrib", "arc-solid-gtk-theme", "papirus-icon-theme",
+    "woff2-font-awesome", "otf-font-awesome",
+    "noto-fonts-emoji", "noto-fonts", "noto-fonts-cjk", "noto-fonts-extra", "terminus-font",
+    "lightdm", "lightdm-gtk-greeter", "sway", "swaybg", "waybar", "mosquitto", "kitty",
+];
+const DEVELOPER_APPS: [&str; 8] = ["fish", "starship", "eza", "neovim", "fastfetch", "btop", "ranger", "python-pillow"];
+const MAIN_PROGRAM: &str = "kite-appimage";
+// Пакеты, которые install.sh ставит до загрузки Kite
+const INSTALL_PACKAGES: [&str; 2] = ["git", "git-lfs"];
+
+const OS_RELEASE: &str = "/etc/os-release";
+const OS_RELEASE_BACKUP: &str = "etc/os-release.backup";
+const PACKAGE_DIR: &str = "$TEMP_DIR/kite";
+
+pub struct PackageTarget {
+    pub name: String,
+    // None - пакет не установлен (для удаления) или не найден
+    pub version: Option<String>,
+}
+
+pub struct FieldChange {
+    pub key: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// Что сделает запуск скрипта, без его выполнения
+pub struct Plan {
+    pub command: String,
+    pub steps: Vec<String>,
+    pub install: Vec<PackageTarget>,
+    pub remove: Vec<PackageTarget>,
+    pub delete_paths: Vec<(PathBuf, bool)>,
+    // Архив настроек, который kite-tools создаст перед запуском
+    pub backup: Option<PathBuf>,
+    pub os_release: Vec<FieldChange>,
+    // Запускается install.sh из загруженного пакета, его действия заранее неизвестны
+    pub package_script: bool,
+    pub reboot: bool,
+    // Ошибки запросов к pacman: план неполон
+    pub errors: Vec<String>,
+}
+
+impl Plan {
+    fn new(command: String, reboot: bool) -> Self {
+        Self {
+            command,
+            steps: Vec::new(),
+            install: Vec::new(),
+            remove: Vec::new(),
+            delete_paths: Vec::new(),
+            backup: None,
+            os_release: Vec::new(),
+            package_script: false,
+            reboot,
+            errors: Vec::new(),
+        }
+    }
+
+    // Установка пакетов через pacman -S: список с зависимостями от самого pacman
+    fn query_install(&mut self, root: &Path, names: &[String]) {
+        match packages::sync_targets(root, names) {
+            Ok(targets) => self.install.extend(targets.into_iter().map(|(name, version)| PackageTarget {
+                name,
+                version: Some(version),
+            })),
+            Err(e) => self.errors.push(e),
+        }
+    }
+
+    // pacman -R без -s удаляет только перечисленные пакеты; отсутствующий пакет прервёт скрипт
+    fn query_remove(&mut self, root: &Path, names: &[String]) {
+        match packages::installed_versions(root, names) {
+            Ok(mut versions) => self.remove.extend(names.iter().map(|name| PackageTarget {
+                name: name.clone(),
+                version: versions.remove(name),
+            })),
+            Err(e) => self.errors.push(format!("pacman: {}", e)),
+        }
+    }
+}
+
+pub(crate) fn install(config: &Config, channel: Channel, command: String, reboot: bool, language: Language) -> Plan {
+    let mut plan = Plan::new(command, reboot);
+    let version = match channel {
+        Channel::Stable => L10N.get("plan_latest_release", language),
+        Channel::Developer | Channel::Experimental => L10N.get_fmt("plan_branch_head", language, channel.as_arg()),
+    };
+
+    plan.steps.push(format!("pacman -Syu --noconfirm {}", INSTALL_PACKAGES.join(" ")));
+    plan.steps.extend(package_steps(config, channel, None, &version));
+    plan.steps.extend(install_steps(config, &version, None));
+    plan.query_install(&config.system_root, &INSTALL_PACKAGES.map(str::to_string));
+    plan.package_script = true;
+
+    let current = OsRelease::load(&config.system_root).ok();
+    plan.os_release.push(change(current.as_ref(), "VERSION_ID", Some(version)));
+    plan
+}
+
+pub(crate) fn uninstall(config: &Config, uninstall_type: UninstallType, home: &Path, command: String, reboot: bool) -> Plan {
+    let mut plan = Plan::new(command, reboot && uninstall_type == UninstallType::Full);
+    if uninstall_type != UninstallType::Apps {
+        plan.backup = backup::planned(&config.system_root, home, &uninstall_type.backup_reason());
+    }
+    add_uninstall(&mut plan, config, uninstall_type, home);
+    plan
+}
+
+// update.sh: полное удаление текущей версии, затем установка новой с заменой BUILD_ID и VERSION_ID
+pub(crate) fn update(config: &Config, channel: Channel, version: &str, home: &Path, command: String, reboot: bool) -> Plan {
+    let mut plan = Plan::new(command, reboot);
+    plan.backup = backup::planned(&config.system_root, home, backup::UPDATE_REASON);
+
+    plan.steps.push("pacman -Syu --noconfirm".to_string());
+    plan.steps.extend(package_steps(config, channel, Some(version), version));
+    add_uninstall(&mut plan, config, UninstallType::Full, home);
+    plan.steps.extend(install_steps(config, version, Some(channel)));
+    plan.package_script = true;
+
+    // os-release из пакета заменяет восстановленную копию, поэтому меняются только эти поля
+    let current = OsRelease::load(&config.system_root).ok();
+    plan.os_release = vec![
+        change(current.as_ref(), "BUILD_ID", Some(channel.as_arg().to_string())),
+        change(current.as_ref(), "VERSION_ID", Some(version.to_string())),
+    ];
+    plan
+}
+
+pub fn install_packages(config: &Config, names: &[String], command: String) -> Plan {
+    let mut plan = Plan::new(command, false);
+    plan.steps.push(format!("pacman -S --needed {}", names.join(" ")));
+    plan.query_install(&config.system_root, names);
+    plan
+}
+
+pub fn config_paths(root: &Path, home: &Path) -> Vec<PathBuf> {
+    HOME_CONFIG_PATHS.iter()
+        .map(|path| home.join(path))
+        .chain(SYSTEM_CONFIG_PATHS.iter().map(|path| root.join(path)))
+        .collect()
+}
+
+fn add_uninstall(plan: &mut Plan, config: &Config, uninstall_type: UninstallType, home: &Path) {
+    let root = &config.system_root;
+    if matches!(uninstall_type, UninstallType::Config | UninstallType::Full) {
+        for path in config_paths(root, home) {
+            plan.steps.push(format!("rm -r {}", path.display()));
+            let exists = path.exists();
+            plan.delete_paths.push((path, exists));
+        }
+    }
+
+    if matches!(uninstall_type, UninstallType::Apps | UninstallType::Full) {
+        plan.steps.push(format!("pacman -R --noconfirm {}", APPS.join(" ")));
+        plan.steps.push(format!("pacman -R --noconfirm {}", DEVELOPER_APPS.join(" ")));
+        plan.steps.push("chsh -s /bin/bash".to_string());
+
+        let apps: Vec<String> = APPS.iter().chain(DEVELOPER_APPS.iter()).map(|name| name.to_string()).collect();
+        plan.query_remove(root, &apps);
+    }
+
+    if uninstall_type == UninstallType::Full {
+        plan.steps.push(format!("pacman -R --noconfirm {}", MAIN_PROGRAM));
+        plan.steps.push(format!("cp /{} {}", OS_RELEASE_BACKUP, OS_RELEASE));
+        plan.query_remove(root, &[MAIN_PROGRAM.to_string()]);
+        plan.os_release = restore_changes(root);
+    }
+}
+
+// Загрузка пакета Kite, как в install.sh и update.sh
+// Стабильная версия клонируется по тегу, остальные - по ветке с переходом на нужный коммит
+fn package_steps(config: &Config, channel: Channel, version: Option<&str>, latest: &str) -> Vec<String> {
+    let branch = match channel {
+        Channel::Stable => version.unwrap_or(latest),
+        Channel::Developer | Channel::Experimental => channel.as_arg(),
+    };
+    let mut steps = vec![format!(
+        "git clone --depth 1 --branch {} https://github.com/{}/{}.git {}",
+        branch, config.github_owner, config.github_repo, PACKAGE_DIR,
+    )];
+    if let (Channel::Developer | Channel::Experimental, Some(version)) = (channel, version) {
+        steps.push(format!("git fetch --depth 1 origin {} && git checkout {}", version, version));
+    }
+    steps.push(format!("git lfs install && git lfs pull ({})", PACKAGE_DIR));
+    steps
+}
+
+fn install_steps(config: &Config, version: &str, channel: Option<Channel>) -> Vec<String> {
+    let mut steps = vec![
+        format!("bash {}/install.sh", PACKAGE_DIR),
+        format!("cp {} /{}", OS_RELEASE, OS_RELEASE_BACKUP),
+        format!("cp {}/os-release /etc/", PACKAGE_DIR),
+        format!("cp {}/uninstall.sh {}/", PACKAGE_DIR, config.scripts_dir.display()),
+    ];
+    if let Some(channel) = channel {
+        steps.push(format!("sed -i s/BUILD_ID=.*$/BUILD_ID={}/ {}", channel.as_arg(), OS_RELEASE));
+    }
+    steps.push(format!("sed -i s/VERSION_ID=.*$/VERSION_ID={}/ {}", version, OS_RELEASE));
+    steps
+}
+
+fn change(current: Option<&OsRelease>, key: &str, to: Option<String>) -> FieldChange {
+    FieldChange {
+        key: key.to_string(),
+        from: current.and_then(|os_release| os_release.get(key)).map(str::to_string),
+        to,
+    }
+}
+
+// Поля, которые изменит возврат os-release из резервной копии
+fn restore_changes(root: &Path) -> Vec<FieldChange> {
+    let current = OsRelease::load(root).ok();
+    let Ok(content) = std::fs::read_to_string(root.join(OS_RELEASE_BACKUP)) else {
+        return Vec::new();
+    };
+    let backup = OsRelease::parse(&content);
+
+    let mut keys: Vec<&String> = backup.fields().keys()
+        .chain(current.iter().flat_map(|current| current.fields().keys()))
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .map(|key| change(current.as_ref(), key, backup.get(key).map(str::to_string)))
+        .filter(|change| change.from != change.to)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNINSTALL_SCRIPT: &str = include_str!("../scripts/uninstall.sh");
+
+    // Команды скрипта с продолжениями строк через \ в одну строку
+    fn script_commands() -> Vec<String> {
+        UNINSTALL_SCRIPT.replace("\\\n", " ").lines().map(str::to_string).collect()
+    }
+
+    // Аргументы каждого `pacman -R --noconfirm ...; then` в порядке скрипта
+    fn removed_packages() -> Vec<Vec<String>> {
+        script_commands().iter()
+            .filter_map(|line| line.split_once("pacman -R --noconfirm "))
+            .map(|(_, args)| args.split_once(';').map_or(args, |(args, _)| args))
+            .map(|args| args.split_whitespace().map(str::to_string).collect())
+            .collect()
+    }
+
+    #[test]
+    fn package_lists_match_uninstall_script() {
+        assert_eq!(removed_packages(), [APPS.to_vec(), DEVELOPER_APPS.to_vec(), vec![MAIN_PROGRAM]]);
+    }
+
+    #[test]
+    fn config_paths_match_uninstall_script() {
+        let commands = script_commands();
+        let paths: Vec<String> = commands.iter()
+            .find_map(|line| line.trim().strip_prefix("for path in "))
+            .and_then(|paths| paths.split_once(';'))
+            .map(|(paths, _)| paths.split_whitespace().map(|path| path.trim_matches('"').to_string()).collect())
+            .unwrap();
+
+        let expected: Vec<String> = HOME_CONFIG_PATHS.iter()
+            .map(|path| format!("$HOME_PATH/{}", path))
+            .chain(SYSTEM_CONFIG_PATHS.iter().map(|path| format!("/{}", path)))
+            .collect();
+        assert_eq!(paths, expected);
+    }
+}
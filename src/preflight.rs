@@ -0,0 +1,241 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use sysinfo::Disks;
+use crate::config::Config;
+use crate::localization::{Language, L10N};
+use crate::process::running_package_manager;
+use crate::summary::format_duration;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+const MIB: u64 = 1024 * 1024;
+
+// Пороги свободного места: (путь, ошибка ниже, предупреждение ниже).
+// В /tmp скрипты клонируют репозиторий вместе с файлами LFS
+const DISK_THRESHOLDS: [(&str, u64, u64); 2] = [
+    ("", 2 * GIB, 5 * GIB),
+    ("tmp", 512 * MIB, GIB),
+];
+
+const PACMAN_LOCK_PATH: &str = "var/lib/pacman/db.lck";
+const POWER_SUPPLY_PATH: &str = "sys/class/power_supply";
+// Ниже этого заряда без сети установка не начинается
+const MIN_BATTERY_CAPACITY: u8 = 20;
+const GITHUB_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Программы, которые вызывают install.sh и update.sh
+const REQUIRED_TOOLS: [&str; 3] = ["git", "git-lfs", "curl"];
+// install.sh сам ставит их через pacman, поэтому при установке их отсутствие не критично
+const INSTALLED_BY_SCRIPT: [&str; 2] = ["git", "git-lfs"];
+
+pub enum PacmanLock {
+    Free,
+    // Файл блокировки есть, но ни один пакетный менеджер не запущен; возраст - по времени изменения
+    Stale { age: Option<Duration> },
+    Held { process: String, pid: u32 },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Operation {
+    Install,
+    Update,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CheckLevel {
+    Pass,
+    Warn,
+    Fail,
+}
+
+pub struct PreflightCheck {
+    pub name: String,
+    pub level: CheckLevel,
+    pub detail: String,
+}
+
+impl PreflightCheck {
+    fn new(name: String, level: CheckLevel, detail: String) -> Self {
+        Self { name, level, detail }
+    }
+}
+
+// Проверки перед запуском install.sh или update.sh, чтобы скрипт не прервался на середине
+pub fn run(config: &Config, operation: Operation, language: Language) -> Vec<PreflightCheck> {
+    let mut checks = disk_checks(&config.system_root, language);
+    checks.extend([
+        pacman_lock_check(&config.system_root, language),
+        github_check(config, language),
+        sudo_check(language),
+        battery_check(&config.system_root, language),
+        tools_check(operation, language),
+    ]);
+    checks
+}
+
+pub fn has_failures(checks: &[PreflightCheck]) -> bool {
+    checks.iter().any(|check| check.level == CheckLevel::Fail)
+}
+
+fn disk_checks(root: &Path, language: Language) -> Vec<PreflightCheck> {
+    let disks = Disks::new_with_refreshed_list();
+    DISK_THRESHOLDS.iter()
+        .map(|&(path, fail_below, warn_below)| {
+            let path = match path {
+                "" => root.to_path_buf(),
+                path => root.join(path),
+            };
+            let name = L10N.get_fmt("preflight_disk", language, &path.display().to_string());
+
+            // Файловая система пути - с самой длинной подходящей точкой монтирования
+            let available = disks.list().iter()
+                .filter(|disk| path.starts_with(disk.mount_point()))
+                .max_by_key(|disk| disk.mount_point().as_os_str().len())
+                .map(|disk| disk.available_space());
+            let Some(available) = available else {
+                return PreflightCheck::new(name, CheckLevel::Warn, L10N.get("preflight_disk_unknown", language));
+            };
+
+            let level = match available {
+                available if available < fail_below => CheckLevel::Fail,
+                available if available < warn_below => CheckLevel::Warn,
+                _ => CheckLevel::Pass,
+            };
+            let detail = L10N.get_fmt_args("preflight_disk_free", language, &[&format_size(available), &format_size(warn_below)]);
+            PreflightCheck::new(name, level, detail)
+        })
+        .collect()
+}
+
+pub fn pacman_lock_path(root: &Path) -> PathBuf {
+    root.join(PACMAN_LOCK_PATH)
+}
+
+pub fn pacman_lock(root: &Path) -> PacmanLock {
+    let Ok(metadata) = fs::metadata(pacman_lock_path(root)) else {
+        return PacmanLock::Free;
+    };
+
+    match running_package_manager() {
+        Some((process, pid)) => PacmanLock::Held { process, pid },
+        None => PacmanLock::Stale {
+            age: metadata.modified().ok().and_then(|modified| SystemTime::now().duration_since(modified).ok()),
+        },
+    }
+}
+
+fn pacman_lock_check(root: &Path, language: Language) -> PreflightCheck {
+    let name = L10N.get("preflight_pacman", language);
+    let (level, detail) = match pacman_lock(root) {
+        PacmanLock::Held { process, pid } => (
+            CheckLevel::Fail,
+            L10N.get_fmt_args("preflight_pacman_busy", language, &[&process, &pid.to_string()]),
+        ),
+        PacmanLock::Stale { age } => (
+            CheckLevel::Fail,
+            L10N.get_fmt("preflight_pacman_stale", language, &age.map_or("?".to_string(), format_duration)),
+        ),
+        // Без блокировки пакетный менеджер может быть между транзакциями
+        PacmanLock::Free => match running_package_manager() {
+            Some((process, pid)) => (
+                CheckLevel::Warn,
+                L10N.get_fmt_args("preflight_pacman_running", language, &[&process, &pid.to_string()]),
+            ),
+            None => (CheckLevel::Pass, L10N.get("preflight_pacman_free", language)),
+        },
+    };
+    PreflightCheck::new(name, level, detail)
+}
+
+// Скрипты клонируют репозиторий с GitHub независимо от release_source
+fn github_check(config: &Config, language: Language) -> PreflightCheck {
+    let name = L10N.get("preflight_github", language);
+    let url = format!("https://github.com/{}/{}", config.github_owner, config.github_repo);
+    let agent = ureq::AgentBuilder::new()
+        .timeout(GITHUB_TIMEOUT)
+        .try_proxy_from_env(true)
+        .build();
+
+    let (level, detail) = match agent.head(&url).call() {
+        Ok(_) => (CheckLevel::Pass, url),
+        Err(ureq::Error::Status(404, _)) => (CheckLevel::Fail, L10N.get_fmt("preflight_github_not_found", language, &url)),
+        Err(ureq::Error::Status(code, _)) => (CheckLevel::Warn, L10N.get_fmt("preflight_github_status", language, &code.to_string())),
+        Err(e) => (CheckLevel::Fail, L10N.get_fmt("preflight_github_unreachable", language, &e.to_string())),
+    };
+    PreflightCheck::new(name, level, detail)
+}
+
+fn sudo_check(language: Language) -> PreflightCheck {
+    let name = L10N.get("preflight_sudo", language);
+    if unsafe { libc::geteuid() } == 0 {
+        return PreflightCheck::new(name, CheckLevel::Pass, L10N.get("preflight_sudo_root", language));
+    }
+
+    match find_in_path("sudo") {
+        Some(path) => PreflightCheck::new(name, CheckLevel::Pass, path.display().to_string()),
+        None => PreflightCheck::new(name, CheckLevel::Fail, L10N.get("preflight_sudo_missing", language)),
+    }
+}
+
+fn battery_check(root: &Path, language: Language) -> PreflightCheck {
+    let name = L10N.get("preflight_power", language);
+    let read = |path: &Path, file: &str| fs::read_to_string(path.join(file)).map(|value| value.trim().to_string()).ok();
+
+    let supplies: Vec<PathBuf> = fs::read_dir(root.join(POWER_SUPPLY_PATH))
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    let on_mains = supplies.iter()
+        .any(|supply| read(supply, "type").as_deref() != Some("Battery") && read(supply, "online").as_deref() == Some("1"));
+    let batteries: Vec<(Option<u8>, bool)> = supplies.iter()
+        .filter(|supply| read(supply, "type").as_deref() == Some("Battery"))
+        .map(|supply| (
+            read(supply, "capacity").and_then(|capacity| capacity.parse().ok()),
+            read(supply, "status").as_deref() == Some("Discharging"),
+        ))
+        .collect();
+
+    let capacity = batteries.iter().filter_map(|(capacity, _)| *capacity).min();
+    let discharging = !on_mains && batteries.iter().any(|(_, discharging)| *discharging);
+    let (level, detail) = match (batteries.is_empty(), discharging, capacity) {
+        (true, _, _) => (CheckLevel::Pass, L10N.get("preflight_power_no_battery", language)),
+        (false, false, _) => (CheckLevel::Pass, L10N.get("preflight_power_ac", language)),
+        (false, true, Some(capacity)) if capacity < MIN_BATTERY_CAPACITY => (
+            CheckLevel::Fail,
+            L10N.get_fmt_args("preflight_power_low", language, &[&capacity.to_string(), &MIN_BATTERY_CAPACITY.to_string()]),
+        ),
+        (false, true, capacity) => (
+            CheckLevel::Warn,
+            L10N.get_fmt("preflight_power_battery", language, &capacity.map_or("?".to_string(), |capacity| capacity.to_string())),
+        ),
+    };
+    PreflightCheck::new(name, level, detail)
+}
+
+fn tools_check(operation: Operation, language: Language) -> PreflightCheck {
+    let name = L10N.get("preflight_tools", language);
+    let missing: Vec<&str> = REQUIRED_TOOLS.into_iter()
+        .filter(|tool| find_in_path(tool).is_none())
+        .collect();
+
+    if missing.is_empty() {
+        return PreflightCheck::new(name, CheckLevel::Pass, REQUIRED_TOOLS.join(", "));
+    }
+    let installed_by_script = operation == Operation::Install
+        && missing.iter().all(|tool| INSTALLED_BY_SCRIPT.contains(tool));
+    let (level, key) = match installed_by_script {
+        true => (CheckLevel::Warn, "preflight_tools_will_install"),
+        false => (CheckLevel::Fail, "preflight_tools_missing"),
+    };
+    PreflightCheck::new(name, level, L10N.get_fmt(key, language, &missing.join(", ")))
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / GIB as f64)
+}
@@ -0,0 +1,253 @@
+use std::io;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, RefreshKind, System};
+
+// Время между SIGTERM и SIGKILL при отмене задачи
+pub const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(3);
+// Ожидание после SIGKILL перед отчётом о выживших процессах
+const TERMINATE_KILL_WAIT: Duration = Duration::from_secs(1);
+const TERMINATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Пакетные менеджеры, которые держат /var/lib/pacman/db.lck и могут оставить его при прерывании
+const PACKAGE_MANAGERS: [&str; 3] = ["pacman", "yay", "paru"];
+
+// Процесс из таблицы: для поиска потомков и пакетных менеджеров достаточно родителя и имени
+struct ProcessEntry {
+    pid: Pid,
+    parent: Option<Pid>,
+    name: String,
+}
+
+// Только таблица процессов: память, диски и сеть для отмены и проверок не нужны
+fn process_table() -> System {
+    System::new_with_specifics(RefreshKind::nothing().with_processes(ProcessRefreshKind::nothing()))
+}
+
+fn entries(system: &System) -> Vec<ProcessEntry> {
+    system.processes()
+        .values()
+        .map(|process| ProcessEntry {
+            pid: process.pid(),
+            parent: process.parent(),
+            name: process.name().to_string_lossy().into_owned(),
+        })
+        .collect()
+}
+
+// Дополняет список процессов всеми их потомками
+fn collect_descendants(processes: &[ProcessEntry], tree: &mut Vec<Pid>) {
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        for process in processes {
+            if process.parent == Some(parent) && !tree.contains(&process.pid) {
+                tree.push(process.pid);
+            }
+        }
+        i += 1;
+    }
+}
+
+fn is_package_manager(name: &str) -> bool {
+    PACKAGE_MANAGERS.contains(&name)
+}
+
+// Пакетный менеджер среди корня и его потомков
+fn transaction_in_tree(root: Pid, processes: &[ProcessEntry]) -> bool {
+    let mut tree = vec![root];
+    collect_descendants(processes, &mut tree);
+    processes.iter()
+        .any(|process| tree.contains(&process.pid) && is_package_manager(&process.name))
+}
+
+fn is_alive(system: &System, pid: Pid) -> bool {
+    system.process(pid)
+        .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+}
+
+// Сигнал группе процессов корня и каждому потомку отдельно:
+// sudo с use_pty запускает команду в собственной сессии.
+// Возвращает процессы, которым сигнал не доставлен из-за прав: скрипт под sudo, pacman
+fn send_signal(root: Pid, tree: &[Pid], signal: libc::c_int) -> Vec<Pid> {
+    unsafe {
+        libc::killpg(root.as_u32() as libc::pid_t, signal);
+    }
+    tree.iter()
+        .copied()
+        .filter(|pid| !kill(*pid, signal))
+        .collect()
+}
+
+// false - нет прав на сигнал процессу; завершившийся процесс считается доставленным
+fn kill(pid: Pid, signal: libc::c_int) -> bool {
+    let result = unsafe { libc::kill(pid.as_u32() as libc::pid_t, signal) };
+    result == 0 || io::Error::last_os_error().raw_os_error() != Some(libc::EPERM)
+}
+
+// Сигнал процессам root через sudo -n: без сохранённых прав sudo не спрашивает пароль, а завершается с ошибкой
+fn send_privileged_signal(tree: &[Pid], signal: &str) -> bool {
+    if tree.is_empty() {
+        return true;
+    }
+    Command::new("sudo")
+        .args(["-n", "kill", &format!("-{}", signal), "--"])
+        .args(tree.iter().map(|pid| pid.to_string()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+// Выполняется ли в дереве процессов транзакция пакетного менеджера
+pub fn package_transaction_running(root: u32) -> bool {
+    transaction_in_tree(Pid::from_u32(root), &entries(&process_table()))
+}
+
+// Любой запущенный пакетный менеджер: имя и PID
+pub fn running_package_manager() -> Option<(String, u32)> {
+    let system = process_table();
+    system.processes()
+        .values()
+        .filter(|process| process.status() != ProcessStatus::Zombie)
+        .find(|process| is_package_manager(&process.name().to_string_lossy()))
+        .map(|process| (process.name().to_string_lossy().into_owned(), process.pid().as_u32()))
+}
+
+// Завершает дерево процессов в фоне: SIGTERM, а по истечении TERMINATE_GRACE_PERIOD - SIGKILL.
+// Корень должен быть лидером своей группы процессов.
+// Через канал приходят процессы, пережившие завершение: имя и PID
+pub fn terminate_tree(root: u32) -> Receiver<Vec<(String, u32)>> {
+    let root = Pid::from_u32(root);
+    let mut system = process_table();
+    let mut tree = vec![root];
+    collect_descendants(&entries(&system), &mut tree);
+
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        // Без sudo -n остаётся SIGTERM самому sudo, который передаёт его команде
+        let denied = send_signal(root, &tree, libc::SIGTERM);
+        send_privileged_signal(&denied, "TERM");
+
+        if !wait_exit(&mut system, &tree, TERMINATE_GRACE_PERIOD) {
+            // Потомки могли успеть запустить новые процессы
+            collect_descendants(&entries(&system), &mut tree);
+            tree.retain(|pid| is_alive(&system, *pid));
+
+            let denied: Vec<Pid> = tree.iter()
+                .copied()
+                .filter(|pid| !kill(*pid, 0))
+                .collect();
+            let stuck = match send_privileged_signal(&denied, "KILL") {
+                true => Vec::new(),
+                false => denied,
+            };
+
+            // SIGKILL для sudo оставил бы его команду без присмотра и с db.lck,
+            // поэтому предки процессов root, которых не завершить, не трогаются
+            let killable: Vec<Pid> = tree.iter()
+                .copied()
+                .filter(|pid| !stuck.iter().any(|stuck| is_ancestor(&system, *pid, *stuck)))
+                .collect();
+            if killable.contains(&root) {
+                send_signal(root, &killable, libc::SIGKILL);
+            } else {
+                for pid in &killable {
+                    kill(*pid, libc::SIGKILL);
+                }
+            }
+            wait_exit(&mut system, &tree, TERMINATE_KILL_WAIT);
+        }
+
+        let survivors = tree.iter()
+            .filter_map(|pid| system.process(*pid).filter(|_| is_alive(&system, *pid)))
+            .map(|process| (process.name().to_string_lossy().into_owned(), process.pid().as_u32()))
+            .collect();
+        tx.send(survivors).unwrap_or_default();
+    });
+    rx
+}
+
+// Ожидание завершения всех процессов дерева; false - кто-то ещё жив
+fn wait_exit(system: &mut System, tree: &[Pid], timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        thread::sleep(TERMINATE_POLL_INTERVAL);
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        if tree.iter().all(|pid| !is_alive(system, *pid)) {
+            return true;
+        }
+    }
+    false
+}
+
+fn is_ancestor(system: &System, ancestor: Pid, pid: Pid) -> bool {
+    let mut current = system.process(pid).and_then(|process| process.parent());
+    while let Some(parent) = current {
+        if parent == ancestor {
+            return true;
+        }
+        current = system.process(parent).and_then(|process| process.parent());
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, parent: u32, name: &str) -> ProcessEntry {
+        ProcessEntry {
+            pid: Pid::from_u32(pid),
+            parent: Some(Pid::from_u32(parent)),
+            name: name.to_string(),
+        }
+    }
+
+    // sudo -> bash update.sh -> pacman; отдельно пользовательский yay
+    fn processes() -> Vec<ProcessEntry> {
+        vec![
+            entry(10, 1, "sudo"),
+            entry(11, 10, "bash"),
+            entry(12, 11, "pacman"),
+            entry(13, 11, "git"),
+            entry(20, 1, "kitty"),
+            entry(21, 20, "yay"),
+        ]
+    }
+
+    #[test]
+    fn package_manager_names() {
+        assert!(is_package_manager("pacman"));
+        assert!(is_package_manager("yay"));
+        assert!(is_package_manager("paru"));
+        assert!(!is_package_manager("pacman-key"));
+        assert!(!is_package_manager("makepkg"));
+        assert!(!is_package_manager("Pacman"));
+    }
+
+    #[test]
+    fn descendants_of_root() {
+        let mut tree = vec![Pid::from_u32(10)];
+        collect_descendants(&processes(), &mut tree);
+        tree.sort();
+        assert_eq!(tree, [10, 11, 12, 13].map(Pid::from_u32));
+    }
+
+    #[test]
+    fn transaction_in_script_tree() {
+        assert!(transaction_in_tree(Pid::from_u32(10), &processes()));
+        assert!(transaction_in_tree(Pid::from_u32(12), &processes()));
+    }
+
+    #[test]
+    fn package_manager_outside_tree_is_ignored() {
+        let mut processes = processes();
+        processes.retain(|process| process.name != "pacman");
+        assert!(!transaction_in_tree(Pid::from_u32(10), &processes));
+        assert!(!transaction_in_tree(Pid::from_u32(99), &processes));
+    }
+}
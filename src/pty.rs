@@ -0,0 +1,119 @@
+use std::fs::File;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+
+// Запускает программу в псевдотерминале, возвращает процесс и ведущую сторону терминала.
+// Команда поглощается, чтобы ведомая сторона закрылась в родителе сразу после запуска.
+pub fn spawn(mut command: Command, cols: u16, rows: u16) -> io::Result<(Child, File)> {
+    let mut master = -1;
+    let mut slave = -1;
+    let size = libc::winsize {
+        ws_row: rows.max(1),
+        ws_col: cols.max(1),
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    let result = unsafe {
+        libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &size)
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let master = unsafe { OwnedFd::from_raw_fd(master) };
+    let slave = unsafe { OwnedFd::from_raw_fd(slave) };
+
+    // Ведущая сторона не должна наследоваться дочерним процессом
+    if unsafe { libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    command
+        .stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+
+    // Новая сессия с терминалом в качестве управляющего, чтобы sudo и read видели tty
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = command.spawn()?;
+    Ok((child, File::from(master)))
+}
+
+// Режим ввода терминала: sudo отключает эхо при запросе пароля
+pub struct InputMode {
+    pub echo: bool,
+}
+
+pub fn input_mode(master: &File) -> io::Result<InputMode> {
+    let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+    if unsafe { libc::tcgetattr(master.as_raw_fd(), &mut termios) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(InputMode {
+        echo: termios.c_lflag & libc::ECHO != 0,
+    })
+}
+
+// Окончания приглашений pacman и скриптов, которые ждут ответа да/нет
+const PROMPT_SUFFIXES: [&str; 6] = ["[Y/n]", "[y/N]", "[y/n]", "(y/n)", "(Y/n)", "(y/N)"];
+
+// Известные приглашения: да/нет, пароль sudo и su, выбор pacman "Enter a number (default=1):"
+pub fn is_known_prompt(line: &str) -> bool {
+    let line = line.trim_end();
+    let lowercase = line.to_lowercase();
+    PROMPT_SUFFIXES.iter().any(|suffix| line.ends_with(suffix))
+        || (line.ends_with(':') && (lowercase.contains("password") || lowercase.contains("(default=")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yes_no_prompts() {
+        assert!(is_known_prompt(":: Proceed with installation? [Y/n] "));
+        assert!(is_known_prompt("Remove old packages? [y/N]"));
+        assert!(is_known_prompt("Continue (y/n)"));
+    }
+
+    #[test]
+    fn password_prompts() {
+        assert!(is_known_prompt("[sudo] password for user: "));
+        assert!(is_known_prompt("Password:"));
+    }
+
+    #[test]
+    fn pacman_provider_choice() {
+        assert!(is_known_prompt("Enter a number (default=1): "));
+    }
+
+    #[test]
+    fn progress_lines_are_not_prompts() {
+        assert!(!is_known_prompt("(3/12) installing sway             [######-----]  45%"));
+        assert!(!is_known_prompt(" kite-tools-1.2.0-1-x86_64   120.5 KiB  1.2 MiB/s 00:00 [####]"));
+        assert!(!is_known_prompt("[INFO] Installing packages"));
+        assert!(!is_known_prompt("Checking keys in keyring:"));
+        assert!(!is_known_prompt(""));
+    }
+
+    #[test]
+    fn answer_after_prompt_is_not_a_prompt() {
+        assert!(!is_known_prompt(":: Proceed with installation? [Y/n] y"));
+        assert!(!is_known_prompt("Password: incorrect"));
+    }
+}
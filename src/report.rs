@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use thiserror::Error;
+use crate::check_cache::{self, CheckReport};
+use crate::os_release::OsRelease;
+use crate::packages;
+use crate::summary::SystemSummary;
+
+// Версия схемы JSON-вывода; увеличивается только при несовместимых изменениях полей
+pub const SCHEMA_VERSION: u32 = 1;
+
+// Общая обёртка для `status`, `check` и `packages` с --json
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+pub fn to_json<T: Serialize>(data: &T) -> String {
+    serde_json::to_string(&Envelope { schema_version: SCHEMA_VERSION, data }).unwrap_or_default()
+}
+
+// Вывод `kite-tools status --json`
+#[derive(Serialize)]
+pub struct StatusReport {
+    pub detected: bool,
+    pub installed: bool,
+    pub channel: Option<String>,
+    pub version: Option<String>,
+    pub backup_exists: bool,
+    pub kernel: Option<String>,
+    // Все поля os-release как есть; null, если файл не прочитан
+    pub os_release: Option<BTreeMap<String, String>>,
+    pub last_check: Option<CheckReport>,
+}
+
+impl StatusReport {
+    pub fn collect(root: &Path, summary: &SystemSummary) -> Self {
+        let os_release = OsRelease::load(root).ok().map(|os_release| {
+            os_release.fields().iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        });
+
+        Self {
+            detected: summary.detected,
+            installed: summary.installed,
+            channel: summary.channel.clone(),
+            version: summary.version.clone(),
+            backup_exists: summary.backup_exists,
+            kernel: summary.kernel.clone(),
+            os_release,
+            last_check: check_cache::load(summary.channel.as_deref(), summary.version.as_deref()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PackagesError {
+    #[error("{}: {source}", path.display())]
+    List { path: PathBuf, source: std::io::Error },
+    #[error("pacman: {0}")]
+    Query(std::io::Error),
+}
+
+// Вывод `kite-tools packages --json`
+#[derive(Serialize)]
+pub struct PackagesReport {
+    pub package_list: PathBuf,
+    pub packages: Vec<PackageStatus>,
+}
+
+#[derive(Serialize)]
+pub struct PackageStatus {
+    pub name: String,
+    pub installed: bool,
+    pub version: Option<String>,
+}
+
+impl PackagesReport {
+    pub fn collect(root: &Path, package_list: PathBuf) -> Result<Self, PackagesError> {
+        let names = packages::read_list(&package_list)
+            .map_err(|source| PackagesError::List { path: package_list.clone(), source })?;
+        let mut versions = packages::installed_versions(root, &names).map_err(PackagesError::Query)?;
+
+        let packages = names.into_iter()
+            .map(|name| {
+                let version = versions.remove(&name);
+                PackageStatus {
+                    installed: version.is_some(),
+                    name,
+                    version,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            package_list,
+            packages,
+        })
+    }
+}
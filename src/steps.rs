@@ -0,0 +1,294 @@
+// Этапы выполнения скриптов по строкам `[INFO] ...`
+
+const INFO_PREFIX: &str = "[INFO] ";
+const ERROR_PREFIX: &str = "Error:";
+
+pub const INSTALL_STEPS: &[&str] = &[
+    "Checking system...",
+    "Updating packages...",
+    "Downloading installation package...",
+    "Initializing Git LFS...",
+    "Creating os-release backup...",
+    "Copying system files...",
+    "Applying new changes to system...",
+    "Cleaning up temporary files...",
+];
+
+pub const UPDATE_STEPS: &[&str] = &[
+    "Checking system...",
+    "Updating packages...",
+    "Downloading installation package...",
+    "Initializing Git LFS...",
+    "Removing old version...",
+    "Running installation script...",
+    "Creating os-release backup...",
+    "Copying system files...",
+    "Applying new changes to system...",
+    "Cleaning up temporary files...",
+];
+
+pub const UNINSTALL_CONFIG_STEPS: &[&str] = &[
+    "Checking system...",
+    "Removing configuration files...",
+];
+
+pub const UNINSTALL_APPS_STEPS: &[&str] = &[
+    "Checking system...",
+    "Removing applications...",
+];
+
+pub const UNINSTALL_FULL_STEPS: &[&str] = &[
+    "Checking system...",
+    "Removing the entire system...",
+    "Removing configuration files...",
+    "Removing applications...",
+    "Restoring os-release...",
+];
+
+// Этапы rollback.sh; список собирается из тех, что нужны для отката
+pub const ROLLBACK_CHECK_STEP: &str = "Checking snapshot...";
+pub const ROLLBACK_REINSTALL_STEP: &str = "Reinstalling previous version...";
+pub const ROLLBACK_PACKAGES_STEP: &str = "Restoring packages...";
+pub const ROLLBACK_CONFIGS_STEP: &str = "Restoring configuration files...";
+pub const ROLLBACK_OS_RELEASE_STEP: &str = "Restoring os-release...";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+pub struct Step {
+    pub title: String,
+    pub state: StepState,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct StepTracker {
+    steps: Vec<Step>,
+    current: Option<usize>,
+}
+
+impl StepTracker {
+    pub fn new(plan: &[&str]) -> Self {
+        let steps = plan.iter()
+            .map(|title| Step {
+                title: title.to_string(),
+                state: StepState::Pending,
+                error: None,
+            })
+            .collect();
+
+        Self { steps, current: None }
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    // Доля завершённых этапов
+    pub fn ratio(&self) -> f64 {
+        if self.steps.is_empty() {
+            return 0.0;
+        }
+
+        let completed = self.steps.iter()
+            .filter(|step| step.state == StepState::Completed)
+            .count();
+        completed as f64 / self.steps.len() as f64
+    }
+
+    pub fn completed(&self) -> usize {
+        self.steps.iter().filter(|step| step.state == StepState::Completed).count()
+    }
+
+    pub fn process_line(&mut self, line: &str) {
+        let line = line.trim();
+
+        if let Some(message) = line.strip_prefix(INFO_PREFIX) {
+            self.start_step(message.trim());
+        } else if line.starts_with(ERROR_PREFIX) {
+            self.fail(line.to_string());
+        }
+    }
+
+    // Завершение скрипта: активный этап завершается успешно или с ошибкой
+    pub fn finish(&mut self, success: bool) {
+        let Some(current) = self.current else {
+            return;
+        };
+
+        let step = &mut self.steps[current];
+        if step.state == StepState::Running {
+            step.state = match success {
+                true => StepState::Completed,
+                false => StepState::Failed,
+            };
+        }
+    }
+
+    fn start_step(&mut self, message: &str) {
+        let start = self.current.map_or(0, |current| current + 1);
+
+        // Запланированный этап ищется только впереди, вложенные скрипты повторяют сообщения
+        let index = match self.steps[start..].iter().position(|step| step.state == StepState::Pending && step.title == message) {
+            Some(offset) => {
+                let index = start + offset;
+                for step in &mut self.steps[start..index] {
+                    step.state = StepState::Completed;
+                }
+                index
+            }
+            None => {
+                self.steps.insert(start, Step {
+                    title: message.to_string(),
+                    state: StepState::Pending,
+                    error: None,
+                });
+                start
+            }
+        };
+
+        if let Some(current) = self.current {
+            if self.steps[current].state == StepState::Running {
+                self.steps[current].state = StepState::Completed;
+            }
+        }
+
+        self.steps[index].state = StepState::Running;
+        self.current = Some(index);
+    }
+
+    fn fail(&mut self, error: String) {
+        let index = match self.current {
+            Some(current) => current,
+            None => {
+                self.steps.insert(0, Step {
+                    title: error.clone(),
+                    state: StepState::Pending,
+                    error: None,
+                });
+                self.current = Some(0);
+                0
+            }
+        };
+
+        let step = &mut self.steps[index];
+        step.state = StepState::Failed;
+        step.error = Some(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use StepState::*;
+
+    const PLAN: &[&str] = &["Checking system...", "Updating packages...", "Copying system files..."];
+
+    fn states(tracker: &StepTracker) -> Vec<StepState> {
+        tracker.steps().iter().map(|step| step.state).collect()
+    }
+
+    fn titles(tracker: &StepTracker) -> Vec<&str> {
+        tracker.steps().iter().map(|step| step.title.as_str()).collect()
+    }
+
+    #[test]
+    fn info_lines_advance_planned_steps() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Checking system...");
+        assert_eq!(states(&tracker), [Running, Pending, Pending]);
+
+        // Прочий вывод и повторы вложенных скриптов этапы не меняют
+        tracker.process_line("resolving dependencies...");
+        tracker.process_line("  [INFO] Updating packages...  ");
+        assert_eq!(states(&tracker), [Completed, Running, Pending]);
+        assert_eq!(tracker.completed(), 1);
+        assert!((tracker.ratio() - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn skipped_steps_are_completed() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Copying system files...");
+        assert_eq!(states(&tracker), [Completed, Completed, Running]);
+    }
+
+    #[test]
+    fn unknown_step_is_inserted_after_current() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Checking system...");
+        tracker.process_line("[INFO] Verifying keyring...");
+        assert_eq!(titles(&tracker), ["Checking system...", "Verifying keyring...", "Updating packages...", "Copying system files..."]);
+        assert_eq!(states(&tracker), [Completed, Running, Pending, Pending]);
+
+        tracker.process_line("[INFO] Updating packages...");
+        assert_eq!(states(&tracker), [Completed, Completed, Running, Pending]);
+    }
+
+    #[test]
+    fn repeated_step_is_not_matched_backwards() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Updating packages...");
+        tracker.process_line("[INFO] Checking system...");
+        assert_eq!(titles(&tracker), ["Checking system...", "Updating packages...", "Checking system...", "Copying system files..."]);
+        assert_eq!(states(&tracker), [Completed, Completed, Running, Pending]);
+    }
+
+    #[test]
+    fn error_line_fails_current_step() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Updating packages...");
+        tracker.process_line("Error: failed to synchronize databases");
+
+        assert_eq!(states(&tracker), [Completed, Failed, Pending]);
+        assert_eq!(tracker.steps()[1].error.as_deref(), Some("Error: failed to synchronize databases"));
+
+        // Ошибочный этап остаётся ошибочным после завершения скрипта
+        tracker.finish(true);
+        assert_eq!(states(&tracker), [Completed, Failed, Pending]);
+    }
+
+    #[test]
+    fn error_before_first_step_becomes_a_step() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("Error: This script must be run as root");
+
+        assert_eq!(titles(&tracker)[0], "Error: This script must be run as root");
+        assert_eq!(states(&tracker), [Failed, Pending, Pending, Pending]);
+    }
+
+    #[test]
+    fn finish_closes_running_step() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Copying system files...");
+        tracker.finish(true);
+        assert_eq!(states(&tracker), [Completed, Completed, Completed]);
+        assert!((tracker.ratio() - 1.0).abs() < f64::EPSILON);
+
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.process_line("[INFO] Updating packages...");
+        tracker.finish(false);
+        assert_eq!(states(&tracker), [Completed, Failed, Pending]);
+    }
+
+    #[test]
+    fn finish_without_steps_does_nothing() {
+        let mut tracker = StepTracker::new(PLAN);
+        tracker.finish(false);
+        assert_eq!(states(&tracker), [Pending, Pending, Pending]);
+
+        let mut tracker = StepTracker::default();
+        tracker.finish(true);
+        assert!(tracker.is_empty());
+        assert_eq!(tracker.ratio(), 0.0);
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use sysinfo::System;
+use crate::localization::{Language, L10N};
+use crate::os_release::OsRelease;
+use crate::update_check::UpdateCheckResult;
+
+// Резервная копия, которую install.sh создаёт перед заменой os-release
+const OS_RELEASE_BACKUP_PATH: &str = "etc/os-release.backup";
+
+// Длина сокращённого хеша коммита, как в git
+const SHORT_HASH_LENGTH: usize = 7;
+
+// Сводка о состоянии системы для главного меню
+pub struct SystemSummary {
+    pub detected: bool,
+    pub installed: bool,
+    pub name: String,
+    pub channel: Option<String>,
+    pub version: Option<String>,
+    pub backup_exists: bool,
+    pub kernel: Option<String>,
+}
+
+impl SystemSummary {
+    pub fn collect(root: &Path) -> Self {
+        let os_release = OsRelease::load(root).ok();
+
+        Self {
+            detected: os_release.is_some(),
+            installed: os_release.as_ref().is_some_and(OsRelease::is_kite),
+            name: os_release.as_ref()
+                .map(|os_release| os_release.display_name().to_string())
+                .unwrap_or_default(),
+            channel: os_release.as_ref().and_then(|os_release| os_release.build_id.clone()),
+            version: os_release.and_then(|os_release| os_release.version_id),
+            backup_exists: root.join(OS_RELEASE_BACKUP_PATH).exists(),
+            kernel: System::kernel_version(),
+        }
+    }
+}
+
+// Для developer и experimental VERSION_ID - полный хеш коммита
+pub fn short_version(version: &str) -> &str {
+    let is_hash = version.len() > SHORT_HASH_LENGTH
+        && version.chars().all(|c| c.is_ascii_hexdigit());
+
+    match is_hash {
+        true => &version[..SHORT_HASH_LENGTH],
+        false => version,
+    }
+}
+
+// Длительность команды или возраст файла блокировки: 42s, 3m 05s, 2h 07m, 1d 4h
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        3600..86400 => format!("{}h {:02}m", seconds / 3600, seconds / 60 % 60),
+        _ => format!("{}d {}h", seconds / 86400, seconds / 3600 % 24),
+    }
+}
+
+// Итог проверки обновлений одной фразой для сводки и подсказки waybar
+pub fn check_status_text(result: &UpdateCheckResult, language: Language) -> String {
+    match result {
+        UpdateCheckResult::UpToDate => L10N.get("summary_check_up_to_date", language),
+        UpdateCheckResult::Available { version } => {
+            L10N.get_fmt("summary_check_available", language, short_version(version))
+        }
+        UpdateCheckResult::Ahead { .. } => L10N.get("summary_check_ahead", language),
+        UpdateCheckResult::Error(_) => L10N.get("summary_check_failed", language),
+    }
+}
+
+pub struct UpdateCheckRecord {
+    pub result: UpdateCheckResult,
+    pub checked_at: DateTime<Local>,
+}
@@ -0,0 +1,108 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+use crate::config::xdg_dir;
+
+const UNIT_NAME: &str = "kite-tools-check";
+// Первая проверка после входа в систему и интервал между проверками
+const BOOT_DELAY: &str = "5min";
+pub const DEFAULT_INTERVAL: &str = "6h";
+
+#[derive(Debug, Error)]
+pub enum TimerError {
+    #[error("cannot determine the systemd user unit directory")]
+    UnitDir,
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("systemctl {command}: {reason}")]
+    Systemctl { command: String, reason: String },
+}
+
+// Пара service и timer для периодического `kite-tools check` от имени пользователя
+pub fn install(interval: &str) -> Result<PathBuf, TimerError> {
+    let dir = unit_dir().ok_or(TimerError::UnitDir)?;
+    fs::create_dir_all(&dir).map_err(|source| TimerError::Io { path: dir.clone(), source })?;
+
+    let executable = env::current_exe().map_err(|source| TimerError::Io { path: PathBuf::from("kite-tools"), source })?;
+    write_unit(&dir.join(format!("{}.service", UNIT_NAME)), &service_unit(&executable))?;
+    write_unit(&dir.join(format!("{}.timer", UNIT_NAME)), &timer_unit(interval))?;
+
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", &format!("{}.timer", UNIT_NAME)])?;
+    Ok(dir)
+}
+
+pub fn remove() -> Result<(), TimerError> {
+    let dir = unit_dir().ok_or(TimerError::UnitDir)?;
+    let timer = dir.join(format!("{}.timer", UNIT_NAME));
+
+    // Таймер мог быть уже отключён вручную, поэтому ошибка systemctl здесь не важна
+    if timer.exists() {
+        systemctl(&["disable", "--now", &format!("{}.timer", UNIT_NAME)]).unwrap_or_default();
+    }
+    for unit in [timer, dir.join(format!("{}.service", UNIT_NAME))] {
+        if unit.exists() {
+            fs::remove_file(&unit).map_err(|source| TimerError::Io { path: unit.clone(), source })?;
+        }
+    }
+
+    systemctl(&["daemon-reload"])
+}
+
+fn service_unit(executable: &Path) -> String {
+    format!(
+        "[Unit]\n\
+        Description=Check for Kite system updates\n\
+        After=network-online.target\n\
+        \n\
+        [Service]\n\
+        Type=oneshot\n\
+        ExecStart={} check --quiet --notify\n\
+        # 10 means an update is available, not a failure\n\
+        SuccessExitStatus=10\n",
+        executable.display(),
+    )
+}
+
+fn timer_unit(interval: &str) -> String {
+    format!(
+        "[Unit]\n\
+        Description=Periodic Kite system update check\n\
+        \n\
+        [Timer]\n\
+        OnBootSec={}\n\
+        OnUnitActiveSec={}\n\
+        \n\
+        [Install]\n\
+        WantedBy=timers.target\n",
+        BOOT_DELAY,
+        interval,
+    )
+}
+
+fn write_unit(path: &Path, content: &str) -> Result<(), TimerError> {
+    fs::write(path, content).map_err(|source| TimerError::Io { path: path.to_path_buf(), source })
+}
+
+fn systemctl(args: &[&str]) -> Result<(), TimerError> {
+    let command = args.join(" ");
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| TimerError::Systemctl { command: command.clone(), reason: e.to_string() })?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(TimerError::Systemctl {
+            command,
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }),
+    }
+}
+
+fn unit_dir() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_CONFIG_HOME", ".config")?.join("systemd/user"))
+}
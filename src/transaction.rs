@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+use thiserror::Error;
+use crate::config::xdg_dir;
+use crate::localization::{Language, L10N};
+use crate::os_release::OsRelease;
+use crate::packages;
+use crate::plan;
+use crate::steps;
+
+// Файлы снимка; их же читает rollback.sh
+const OS_RELEASE_FILE: &str = "os-release";
+const OS_RELEASE_BACKUP_FILE: &str = "os-release.backup";
+const PACKAGES_FILE: &str = "packages.txt";
+
+const OS_RELEASE: &str = "etc/os-release";
+const OS_RELEASE_BACKUP: &str = "etc/os-release.backup";
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("cannot determine the snapshot directory")]
+    Dir,
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("pacman: {0}")]
+    Pacman(std::io::Error),
+}
+
+// Состояние системы перед обновлением, к которому возвращает откат
+pub struct Snapshot {
+    pub dir: PathBuf,
+    os_release: String,
+    os_release_backup: Option<String>,
+    packages: Vec<String>,
+    // Архив настроек, снятый перед обновлением
+    config_backup: Option<PathBuf>,
+    config_paths: Vec<PathBuf>,
+}
+
+// Что вернуть после неудачного обновления; пустой откат - система не менялась
+pub struct Rollback {
+    // Канал и версия из снимка для переустановки
+    pub reinstall: Option<(String, String)>,
+    pub missing_packages: Vec<String>,
+    pub restore_configs: bool,
+    pub restore_os_release: bool,
+}
+
+impl Snapshot {
+    pub fn take(root: &Path, home: &Path, config_backup: Option<PathBuf>) -> Result<Self, SnapshotError> {
+        let os_release = read(&root.join(OS_RELEASE))?;
+        let os_release_backup = fs::read_to_string(root.join(OS_RELEASE_BACKUP)).ok();
+        let packages = packages::explicit_packages(root).map_err(SnapshotError::Pacman)?;
+        let config_paths = plan::config_paths(root, home).into_iter()
+            .filter(|path| path.symlink_metadata().is_ok())
+            .collect();
+
+        let dir = snapshot_dir().ok_or(SnapshotError::Dir)?
+            .join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+        fs::create_dir_all(&dir).map_err(|source| SnapshotError::Io { path: dir.clone(), source })?;
+
+        write(&dir.join(OS_RELEASE_FILE), &os_release)?;
+        if let Some(backup) = &os_release_backup {
+            write(&dir.join(OS_RELEASE_BACKUP_FILE), backup)?;
+        }
+        let mut package_list = packages.join("\n");
+        package_list.push('\n');
+        write(&dir.join(PACKAGES_FILE), &package_list)?;
+
+        Ok(Self {
+            dir,
+            os_release,
+            os_release_backup,
+            packages,
+            config_backup,
+            config_paths,
+        })
+    }
+
+    // Сравнение снимка с текущей системой после ошибки update.sh.
+    // Ошибка до удаления старой версии ничего не меняет, кроме обновлённых pacman -Syu пакетов
+    pub fn rollback(&self, root: &Path) -> Rollback {
+        let os_release = fs::read_to_string(root.join(OS_RELEASE)).unwrap_or_default();
+        let os_release_backup = fs::read_to_string(root.join(OS_RELEASE_BACKUP)).ok();
+        let installed = packages::installed_versions(root, &self.packages).unwrap_or_default();
+        let configs_missing = self.config_paths.iter().any(|path| path.symlink_metadata().is_err());
+
+        self.compare(&os_release, os_release_backup.as_deref(), &installed, configs_missing)
+    }
+
+    fn compare(&self, os_release: &str, os_release_backup: Option<&str>, installed: &HashMap<String, String>, configs_missing: bool) -> Rollback {
+        let missing_packages: Vec<String> = self.packages.iter()
+            .filter(|name| !installed.contains_key(*name))
+            .cloned()
+            .collect();
+
+        // Удаление успело начаться или новая версия уже установлена: возвращается прежняя
+        let reinstall = (!missing_packages.is_empty() || os_release != self.os_release).then(|| {
+            let previous = OsRelease::parse(&self.os_release);
+            (previous.build_id.unwrap_or_default(), previous.version_id.unwrap_or_default())
+        });
+
+        // install.sh новой или прежней версии перезаписывает настройки, поэтому после переустановки они возвращаются всегда
+        let restore_configs = self.config_backup.is_some() && (reinstall.is_some() || configs_missing);
+
+        Rollback {
+            reinstall,
+            missing_packages,
+            restore_configs,
+            restore_os_release: os_release != self.os_release || os_release_backup != self.os_release_backup.as_deref(),
+        }
+    }
+
+    // Аргументы rollback.sh
+    pub fn rollback_args(&self, rollback: &Rollback) -> Vec<String> {
+        let mut args = vec!["--snapshot".to_string(), self.dir.display().to_string()];
+        if rollback.reinstall.is_some() {
+            args.push("--reinstall".to_string());
+        }
+        if rollback.reinstall.is_some() || !rollback.missing_packages.is_empty() {
+            args.push("--packages".to_string());
+        }
+        if let (true, Some(archive)) = (rollback.restore_configs, &self.config_backup) {
+            args.extend(["--configs".to_string(), archive.display().to_string()]);
+        }
+        args
+    }
+
+    // Снимок удаляется после успешного обновления или отката; архив настроек остаётся в списке восстановления
+    pub fn discard(self) {
+        fs::remove_dir_all(&self.dir).unwrap_or_default();
+    }
+}
+
+impl Rollback {
+    pub fn is_empty(&self) -> bool {
+        self.reinstall.is_none() && self.missing_packages.is_empty() && !self.restore_configs && !self.restore_os_release
+    }
+
+    pub fn steps(&self) -> Vec<&'static str> {
+        let mut steps = vec![steps::ROLLBACK_CHECK_STEP];
+        if self.reinstall.is_some() {
+            steps.push(steps::ROLLBACK_REINSTALL_STEP);
+        }
+        if self.reinstall.is_some() || !self.missing_packages.is_empty() {
+            steps.push(steps::ROLLBACK_PACKAGES_STEP);
+        }
+        if self.restore_configs {
+            steps.push(steps::ROLLBACK_CONFIGS_STEP);
+        }
+        steps.push(steps::ROLLBACK_OS_RELEASE_STEP);
+        steps
+    }
+
+    // Что возвращено, для итогового сообщения
+    pub fn describe(&self, language: Language) -> Vec<String> {
+        let mut actions = Vec::new();
+        if let Some((channel, version)) = &self.reinstall {
+            actions.push(L10N.get_fmt_args("rollback_reinstalled", language, &[channel, version]));
+        }
+        if !self.missing_packages.is_empty() {
+            actions.push(L10N.get_fmt("rollback_packages", language, &self.missing_packages.join(" ")));
+        }
+        if self.restore_configs {
+            actions.push(L10N.get("rollback_configs", language));
+        }
+        if self.restore_os_release {
+            actions.push(L10N.get("rollback_os_release", language));
+        }
+        actions
+    }
+}
+
+fn read(path: &Path) -> Result<String, SnapshotError> {
+    fs::read_to_string(path).map_err(|source| SnapshotError::Io { path: path.to_path_buf(), source })
+}
+
+fn write(path: &Path, content: &str) -> Result<(), SnapshotError> {
+    fs::write(path, content).map_err(|source| SnapshotError::Io { path: path.to_path_buf(), source })
+}
+
+fn snapshot_dir() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_STATE_HOME", ".local/state")?.join("kite-tools/snapshots"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREVIOUS: &str = "NAME=\"Kite\"\nBUILD_ID=stable\nVERSION_ID=1.1.0\n";
+    const UPDATED: &str = "NAME=\"Kite\"\nBUILD_ID=stable\nVERSION_ID=1.2.0\n";
+
+    fn snapshot(config_backup: bool) -> Snapshot {
+        Snapshot {
+            dir: PathBuf::from("/nonexistent"),
+            os_release: PREVIOUS.to_string(),
+            os_release_backup: Some("NAME=\"Arch Linux\"\n".to_string()),
+            packages: vec!["sway".to_string(), "waybar".to_string()],
+            config_backup: config_backup.then(|| PathBuf::from("/nonexistent/configs.tar.gz")),
+            config_paths: Vec::new(),
+        }
+    }
+
+    fn installed(names: &[&str]) -> HashMap<String, String> {
+        names.iter().map(|name| (name.to_string(), "1.0-1".to_string())).collect()
+    }
+
+    #[test]
+    fn failure_before_uninstall_needs_no_rollback() {
+        let snapshot = snapshot(true);
+        let rollback = snapshot.compare(PREVIOUS, snapshot.os_release_backup.as_deref(), &installed(&["sway", "waybar"]), false);
+
+        assert!(rollback.is_empty());
+        assert_eq!(snapshot.rollback_args(&rollback), ["--snapshot", "/nonexistent"]);
+    }
+
+    #[test]
+    fn failure_during_install_reinstalls_previous_version() {
+        let snapshot = snapshot(true);
+        let rollback = snapshot.compare(PREVIOUS, snapshot.os_release_backup.as_deref(), &installed(&["waybar"]), true);
+
+        assert_eq!(rollback.reinstall, Some(("stable".to_string(), "1.1.0".to_string())));
+        assert_eq!(rollback.missing_packages, ["sway"]);
+        assert!(rollback.restore_configs);
+        assert!(!rollback.restore_os_release);
+        assert_eq!(rollback.steps(), [
+            steps::ROLLBACK_CHECK_STEP,
+            steps::ROLLBACK_REINSTALL_STEP,
+            steps::ROLLBACK_PACKAGES_STEP,
+            steps::ROLLBACK_CONFIGS_STEP,
+            steps::ROLLBACK_OS_RELEASE_STEP,
+        ]);
+        assert_eq!(snapshot.rollback_args(&rollback), [
+            "--snapshot", "/nonexistent", "--reinstall", "--packages", "--configs", "/nonexistent/configs.tar.gz",
+        ]);
+    }
+
+    #[test]
+    fn failure_after_os_release_rewrite_restores_everything() {
+        let snapshot = snapshot(true);
+        let rollback = snapshot.compare(UPDATED, Some(PREVIOUS), &installed(&["sway", "waybar"]), false);
+
+        assert_eq!(rollback.reinstall, Some(("stable".to_string(), "1.1.0".to_string())));
+        assert!(rollback.missing_packages.is_empty());
+        assert!(rollback.restore_configs);
+        assert!(rollback.restore_os_release);
+        assert_eq!(rollback.steps(), [
+            steps::ROLLBACK_CHECK_STEP,
+            steps::ROLLBACK_REINSTALL_STEP,
+            steps::ROLLBACK_PACKAGES_STEP,
+            steps::ROLLBACK_CONFIGS_STEP,
+            steps::ROLLBACK_OS_RELEASE_STEP,
+        ]);
+    }
+
+    #[test]
+    fn changed_os_release_backup_alone_is_restored() {
+        let snapshot = snapshot(true);
+        let rollback = snapshot.compare(PREVIOUS, None, &installed(&["sway", "waybar"]), false);
+
+        assert!(rollback.reinstall.is_none());
+        assert!(!rollback.restore_configs);
+        assert!(rollback.restore_os_release);
+        assert!(!rollback.is_empty());
+    }
+
+    #[test]
+    fn missing_configs_are_restored_without_reinstall() {
+        let snapshot = snapshot(true);
+        let rollback = snapshot.compare(PREVIOUS, snapshot.os_release_backup.as_deref(), &installed(&["sway", "waybar"]), true);
+
+        assert!(rollback.reinstall.is_none());
+        assert!(rollback.restore_configs);
+        assert_eq!(rollback.steps(), [steps::ROLLBACK_CHECK_STEP, steps::ROLLBACK_CONFIGS_STEP, steps::ROLLBACK_OS_RELEASE_STEP]);
+    }
+
+    #[test]
+    fn configs_are_not_restored_without_backup() {
+        let snapshot = snapshot(false);
+        let rollback = snapshot.compare(UPDATED, Some(PREVIOUS), &installed(&["waybar"]), true);
+
+        assert!(!rollback.restore_configs);
+        assert!(!snapshot.rollback_args(&rollback).contains(&"--configs".to_string()));
+    }
+}
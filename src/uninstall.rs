@@ -0,0 +1,33 @@
+use clap::ValueEnum;
+use crate::steps;
+
+// Вариант удаления: аргумент uninstall.sh
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum UninstallType {
+    Config,
+    Apps,
+    Full,
+}
+
+impl UninstallType {
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            UninstallType::Config => "config",
+            UninstallType::Apps => "apps",
+            UninstallType::Full => "full",
+        }
+    }
+
+    pub fn steps(&self) -> &'static [&'static str] {
+        match self {
+            UninstallType::Config => steps::UNINSTALL_CONFIG_STEPS,
+            UninstallType::Apps => steps::UNINSTALL_APPS_STEPS,
+            UninstallType::Full => steps::UNINSTALL_FULL_STEPS,
+        }
+    }
+
+    // Подпись архива настроек: uninstall-config, uninstall-full
+    pub fn backup_reason(&self) -> String {
+        format!("uninstall-{}", self.as_arg())
+    }
+}
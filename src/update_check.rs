@@ -0,0 +1,521 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use thiserror::Error;
+use crate::channel::Channel;
+use crate::config::Config;
+use crate::version::{compare_releases, same_commit};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Сколько релизов или коммитов показывать в списке версий
+const VERSIONS_LIMIT: usize = 30;
+const USER_AGENT: &str = concat!("kite-tools/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Error)]
+pub enum ReleaseError {
+    #[error("{0}")]
+    Http(#[from] Box<ureq::Error>),
+    #[error("{}: {source}", path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("invalid response from {location}: {reason}")]
+    Format { location: String, reason: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UpdateCheckResult {
+    UpToDate,
+    Available { version: String },
+    // Установленная версия новее последней в источнике: обновление было бы понижением
+    Ahead { version: String },
+    Error(String),
+}
+
+// Положение коммита head относительно base, как в GitHub compare API
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitStatus {
+    Ahead,
+    Behind,
+    Identical,
+    Diverged,
+}
+
+// Релиз канала stable или коммит ветки в списке версий
+#[derive(Clone)]
+pub struct VersionEntry {
+    // Тег релиза или полный хеш коммита, как его принимает update.sh -v
+    pub version: String,
+    // Название релиза или первая строка сообщения коммита
+    pub title: String,
+    pub date: Option<String>,
+    pub notes: String,
+    pub prerelease: bool,
+}
+
+// Изменения между установленной версией и предлагаемой
+pub enum Changelog {
+    // Описание релиза в markdown
+    ReleaseNotes(String),
+    // Коммиты ветки, от новых к старым
+    Commits(Vec<VersionEntry>),
+}
+
+// Источник сведений о релизах: GitHub или его локальная замена
+pub trait ReleaseSource: Send {
+    // Тег последнего релиза для канала stable
+    fn latest_release(&self) -> Result<String, ReleaseError>;
+
+    // Хеш последнего коммита ветки для каналов developer и experimental
+    fn branch_head(&self, branch: &str) -> Result<String, ReleaseError>;
+
+    // Положение коммита head относительно base
+    fn compare_commits(&self, base: &str, head: &str) -> Result<CommitStatus, ReleaseError>;
+
+    // Последние релизы, от новых к старым
+    fn releases(&self) -> Result<Vec<VersionEntry>, ReleaseError>;
+
+    // Последние коммиты ветки, от новых к старым
+    fn branch_commits(&self, branch: &str) -> Result<Vec<VersionEntry>, ReleaseError>;
+
+    // Релиз с указанным тегом
+    fn release(&self, tag: &str) -> Result<VersionEntry, ReleaseError>;
+
+    // Коммиты после base до head включительно, от новых к старым
+    fn commit_log(&self, base: &str, head: &str) -> Result<Vec<VersionEntry>, ReleaseError>;
+}
+
+// Поля ответов GitHub REST API, которые нужны для проверки
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    name: Option<String>,
+    published_at: Option<String>,
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+}
+
+#[derive(Deserialize)]
+struct Branch {
+    commit: Commit,
+}
+
+#[derive(Deserialize)]
+struct Commit {
+    sha: String,
+    commit: Option<CommitDetails>,
+}
+
+#[derive(Deserialize)]
+struct CommitDetails {
+    message: String,
+    committer: Option<Signature>,
+}
+
+#[derive(Deserialize)]
+struct Signature {
+    date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Comparison {
+    status: CommitStatus,
+    #[serde(default)]
+    commits: Vec<Commit>,
+}
+
+const LATEST_RELEASE_PATH: &str = "releases/latest";
+const BRANCHES_PATH: &str = "branches";
+const COMPARE_PATH: &str = "compare";
+const RELEASES_PATH: &str = "releases";
+const RELEASE_TAGS_PATH: &str = "releases/tags";
+const COMMITS_PATH: &str = "commits";
+// В каталоге список релизов лежит рядом с releases/latest
+const RELEASES_INDEX_FILE: &str = "index.json";
+
+// Черновики не показываются: update.sh не может их установить
+fn release_entries(releases: Vec<Release>) -> Vec<VersionEntry> {
+    releases.into_iter()
+        .filter(|release| !release.draft)
+        .take(VERSIONS_LIMIT)
+        .map(release_entry)
+        .collect()
+}
+
+fn release_entry(release: Release) -> VersionEntry {
+    VersionEntry {
+        title: release.name.filter(|name| !name.is_empty()).unwrap_or(release.tag_name.clone()),
+        version: release.tag_name,
+        date: release.published_at.as_deref().map(iso_date),
+        notes: release.body.unwrap_or_default(),
+        prerelease: release.prerelease,
+    }
+}
+
+fn commit_entries(commits: Vec<Commit>) -> Vec<VersionEntry> {
+    commits.into_iter()
+        .take(VERSIONS_LIMIT)
+        .map(commit_entry)
+        .collect()
+}
+
+fn commit_entry(commit: Commit) -> VersionEntry {
+    let details = commit.commit;
+    let message = details.as_ref().map(|details| details.message.as_str()).unwrap_or_default();
+    let (title, notes) = message.split_once('\n').unwrap_or((message, ""));
+
+    VersionEntry {
+        title: title.trim().to_string(),
+        notes: notes.trim().to_string(),
+        date: details.as_ref()
+            .and_then(|details| details.committer.as_ref())
+            .and_then(|committer| committer.date.as_deref())
+            .map(iso_date),
+        version: commit.sha,
+        prerelease: false,
+    }
+}
+
+// В ответе compare коммиты идут от старых к новым
+fn comparison_log(comparison: Comparison) -> Vec<VersionEntry> {
+    comparison.commits.into_iter().rev().map(commit_entry).collect()
+}
+
+// 2024-05-01T12:00:00Z -> 2024-05-01
+fn iso_date(timestamp: &str) -> String {
+    timestamp.split('T').next().unwrap_or(timestamp).to_string()
+}
+
+// Сервер с разметкой GitHub REST API: {base}/releases/latest, {base}/releases, {base}/releases/tags/{tag},
+// {base}/branches/{branch}, {base}/commits?sha={branch} и {base}/compare/{base}...{head}
+pub struct HttpSource {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+impl HttpSource {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build(),
+        }
+    }
+
+    pub fn github(owner: &str, repo: &str) -> Self {
+        Self::new(&format!("{}/repos/{}/{}", GITHUB_API_URL, owner, repo))
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ReleaseError> {
+        let url = format!("{}/{}", self.base_url, path);
+        let response = self.agent.get(&url)
+            .set("Accept", "application/vnd.github+json")
+            .call()
+            .map_err(Box::new)?;
+
+        response.into_json()
+            .map_err(|e| ReleaseError::Format { location: url, reason: e.to_string() })
+    }
+}
+
+impl ReleaseSource for HttpSource {
+    fn latest_release(&self) -> Result<String, ReleaseError> {
+        let release: Release = self.get(LATEST_RELEASE_PATH)?;
+        Ok(release.tag_name)
+    }
+
+    fn branch_head(&self, branch: &str) -> Result<String, ReleaseError> {
+        let branch: Branch = self.get(&format!("{}/{}", BRANCHES_PATH, branch))?;
+        Ok(branch.commit.sha)
+    }
+
+    fn compare_commits(&self, base: &str, head: &str) -> Result<CommitStatus, ReleaseError> {
+        let comparison: Comparison = self.get(&format!("{}/{}...{}", COMPARE_PATH, base, head))?;
+        Ok(comparison.status)
+    }
+
+    fn releases(&self) -> Result<Vec<VersionEntry>, ReleaseError> {
+        let releases = self.get(&format!("{}?per_page={}", RELEASES_PATH, VERSIONS_LIMIT))?;
+        Ok(release_entries(releases))
+    }
+
+    fn branch_commits(&self, branch: &str) -> Result<Vec<VersionEntry>, ReleaseError> {
+        let commits = self.get(&format!("{}?sha={}&per_page={}", COMMITS_PATH, branch, VERSIONS_LIMIT))?;
+        Ok(commit_entries(commits))
+    }
+
+    fn release(&self, tag: &str) -> Result<VersionEntry, ReleaseError> {
+        let release = self.get(&format!("{}/{}", RELEASE_TAGS_PATH, tag))?;
+        Ok(release_entry(release))
+    }
+
+    fn commit_log(&self, base: &str, head: &str) -> Result<Vec<VersionEntry>, ReleaseError> {
+        let comparison = self.get(&format!("{}/{}...{}", COMPARE_PATH, base, head))?;
+        Ok(comparison_log(comparison))
+    }
+}
+
+// Та же разметка в каталоге на диске, для проверки без сети;
+// списки лежат в releases/index.json и commits/{branch}
+pub struct FileSource {
+    dir: PathBuf,
+}
+
+impl FileSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn read<T: DeserializeOwned>(&self, path: &str) -> Result<T, ReleaseError> {
+        let path = self.dir.join(path);
+        let content = fs::read_to_string(&path)
+            .map_err(|source| ReleaseError::Read { path: path.clone(), source })?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| ReleaseError::Format { location: path.display().to_string(), reason: e.to_string() })
+    }
+}
+
+impl ReleaseSource for FileSource {
+    fn latest_release(&self) -> Result<String, ReleaseError> {
+        let release: Release = self.read(LATEST_RELEASE_PATH)?;
+        Ok(release.tag_name)
+    }
+
+    fn branch_head(&self, branch: &str) -> Result<String, ReleaseError> {
+        let branch: Branch = self.read(&format!("{}/{}", BRANCHES_PATH, branch))?;
+        Ok(branch.commit.sha)
+    }
+
+    fn compare_commits(&self, base: &str, head: &str) -> Result<CommitStatus, ReleaseError> {
+        let comparison: Comparison = self.read(&format!("{}/{}...{}", COMPARE_PATH, base, head))?;
+        Ok(comparison.status)
+    }
+
+    fn releases(&self) -> Result<Vec<VersionEntry>, ReleaseError> {
+        let releases = self.read(&format!("{}/{}", RELEASES_PATH, RELEASES_INDEX_FILE))?;
+        Ok(release_entries(releases))
+    }
+
+    fn branch_commits(&self, branch: &str) -> Result<Vec<VersionEntry>, ReleaseError> {
+        let commits = self.read(&format!("{}/{}", COMMITS_PATH, branch))?;
+        Ok(commit_entries(commits))
+    }
+
+    fn release(&self, tag: &str) -> Result<VersionEntry, ReleaseError> {
+        let release = self.read(&format!("{}/{}", RELEASE_TAGS_PATH, tag))?;
+        Ok(release_entry(release))
+    }
+
+    fn commit_log(&self, base: &str, head: &str) -> Result<Vec<VersionEntry>, ReleaseError> {
+        let comparison = self.read(&format!("{}/{}...{}", COMPARE_PATH, base, head))?;
+        Ok(comparison_log(comparison))
+    }
+}
+
+// release_source в настройках: URL сервера, file:// или путь к каталогу; по умолчанию GitHub
+pub fn release_source(config: &Config) -> Box<dyn ReleaseSource> {
+    match config.release_source.as_deref() {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => Box::new(HttpSource::new(url)),
+        Some(path) => Box::new(FileSource::new(path.strip_prefix("file://").unwrap_or(path))),
+        None => Box::new(HttpSource::github(&config.github_owner, &config.github_repo)),
+    }
+}
+
+pub(crate) fn list_versions(source: &dyn ReleaseSource, channel: Channel) -> Result<Vec<VersionEntry>, ReleaseError> {
+    match channel {
+        Channel::Stable => source.releases(),
+        Channel::Developer | Channel::Experimental => source.branch_commits(channel.as_arg()),
+    }
+}
+
+// Последний релиз для stable или head ветки для developer и experimental
+pub(crate) fn latest_version(source: &dyn ReleaseSource, channel: Channel) -> Result<String, ReleaseError> {
+    match channel {
+        Channel::Stable => non_empty(source.latest_release()?),
+        Channel::Developer | Channel::Experimental => non_empty(source.branch_head(channel.as_arg())?),
+    }
+}
+
+// Описание релиза для stable, коммиты между версиями для developer и experimental
+pub(crate) fn changelog(source: &dyn ReleaseSource, channel: Channel, current_version: Option<&str>, target: &str) -> Result<Changelog, ReleaseError> {
+    match (channel, current_version) {
+        (Channel::Stable, _) => Ok(Changelog::ReleaseNotes(source.release(target)?.notes)),
+        (_, Some(current_version)) => Ok(Changelog::Commits(source.commit_log(current_version, target)?)),
+        (_, None) => Ok(Changelog::Commits(Vec::new())),
+    }
+}
+
+pub(crate) fn check(source: &dyn ReleaseSource, channel: Channel, current_version: Option<&str>) -> UpdateCheckResult {
+    let result = match channel {
+        Channel::Stable => check_release(source, current_version),
+        Channel::Developer | Channel::Experimental => check_branch(source, channel.as_arg(), current_version),
+    };
+
+    result.unwrap_or_else(|e| UpdateCheckResult::Error(e.to_string()))
+}
+
+fn check_release(source: &dyn ReleaseSource, current_version: Option<&str>) -> Result<UpdateCheckResult, ReleaseError> {
+    let latest = non_empty(source.latest_release()?)?;
+    let Some(current_version) = current_version else {
+        return Ok(UpdateCheckResult::Available { version: latest });
+    };
+
+    // Теги, не являющиеся версиями, сравниваются как строки
+    let ordering = compare_releases(&latest, current_version)
+        .unwrap_or(match latest == current_version {
+            true => Ordering::Equal,
+            false => Ordering::Greater,
+        });
+
+    Ok(match ordering {
+        Ordering::Greater => UpdateCheckResult::Available { version: latest },
+        Ordering::Equal => UpdateCheckResult::UpToDate,
+        Ordering::Less => UpdateCheckResult::Ahead { version: latest },
+    })
+}
+
+fn check_branch(source: &dyn ReleaseSource, branch: &str, current_version: Option<&str>) -> Result<UpdateCheckResult, ReleaseError> {
+    let head = non_empty(source.branch_head(branch)?)?;
+    let Some(current_version) = current_version else {
+        return Ok(UpdateCheckResult::Available { version: head });
+    };
+    if same_commit(&head, current_version) {
+        return Ok(UpdateCheckResult::UpToDate);
+    }
+
+    // Ошибка сравнения - ошибка проверки, а не повод предложить обновление;
+    // разошедшаяся с веткой установка переходит на её head
+    let status = source.compare_commits(current_version, &head)?;
+    Ok(match status {
+        CommitStatus::Ahead | CommitStatus::Diverged => UpdateCheckResult::Available { version: head },
+        CommitStatus::Identical => UpdateCheckResult::UpToDate,
+        CommitStatus::Behind => UpdateCheckResult::Ahead { version: head },
+    })
+}
+
+fn non_empty(version: String) -> Result<String, ReleaseError> {
+    match version.trim() {
+        "" => Err(ReleaseError::Format { location: "release source".to_string(), reason: "empty version".to_string() }),
+        version => Ok(version.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempTree;
+
+    const HEAD: &str = "5d1c2b3a4f5e6d7c8b9a0f1e2d3c4b5a6f7e8d9c";
+    const INSTALLED: &str = "0a1b2c3d4e5f60718293a4b5c6d7e8f901234567";
+
+    // Источник читает временное дерево, пока оно живо
+    fn file_source(files: &[(&str, &str)]) -> (TempTree, FileSource) {
+        let tree = TempTree::new(files);
+        let source = FileSource::new(tree.path());
+        (tree, source)
+    }
+
+    fn latest(tag: &str) -> String {
+        format!(r#"{{"tag_name": "{}"}}"#, tag)
+    }
+
+    fn branch(sha: &str) -> String {
+        format!(r#"{{"commit": {{"sha": "{}"}}}}"#, sha)
+    }
+
+    fn comparison(status: &str) -> String {
+        format!(r#"{{"status": "{}", "commits": []}}"#, status)
+    }
+
+    fn compare_path() -> String {
+        format!("{}/{}...{}", COMPARE_PATH, INSTALLED, HEAD)
+    }
+
+    fn check_branch_status(status: &str) -> UpdateCheckResult {
+        let (_tree, source) = file_source(&[
+            ("branches/developer", &branch(HEAD)),
+            (&compare_path(), &comparison(status)),
+        ]);
+        check(&source, Channel::Developer, Some(INSTALLED))
+    }
+
+    #[test]
+    fn release_up_to_date() {
+        let (_tree, source) = file_source(&[(LATEST_RELEASE_PATH, &latest("v1.3.0"))]);
+        assert_eq!(check(&source, Channel::Stable, Some("v1.3.0")), UpdateCheckResult::UpToDate);
+        // Префикс v и сокращённая версия не делают релиз новым
+        assert_eq!(check(&source, Channel::Stable, Some("1.3")), UpdateCheckResult::UpToDate);
+    }
+
+    #[test]
+    fn release_available() {
+        let (_tree, source) = file_source(&[(LATEST_RELEASE_PATH, &latest("v1.10.0"))]);
+        let expected = UpdateCheckResult::Available { version: "v1.10.0".to_string() };
+        assert_eq!(check(&source, Channel::Stable, Some("v1.9.0")), expected);
+        assert_eq!(check(&source, Channel::Stable, None), expected);
+    }
+
+    #[test]
+    fn release_newer_installed() {
+        let (_tree, source) = file_source(&[(LATEST_RELEASE_PATH, &latest("v1.3.0"))]);
+        let expected = UpdateCheckResult::Ahead { version: "v1.3.0".to_string() };
+        assert_eq!(check(&source, Channel::Stable, Some("v1.4.0-rc1")), expected);
+    }
+
+    #[test]
+    fn release_error() {
+        let (_tree, source) = file_source(&[]);
+        assert!(matches!(check(&source, Channel::Stable, Some("v1.3.0")), UpdateCheckResult::Error(_)));
+
+        let (_tree, source) = file_source(&[(LATEST_RELEASE_PATH, &latest(" "))]);
+        assert!(matches!(check(&source, Channel::Stable, Some("v1.3.0")), UpdateCheckResult::Error(_)));
+    }
+
+    #[test]
+    fn branch_up_to_date() {
+        let (_tree, source) = file_source(&[("branches/developer", &branch(HEAD))]);
+        assert_eq!(check(&source, Channel::Developer, Some(HEAD)), UpdateCheckResult::UpToDate);
+        // Сокращённый хеш из os-release, сравнение не запрашивается
+        assert_eq!(check(&source, Channel::Developer, Some(&HEAD[..7])), UpdateCheckResult::UpToDate);
+        assert_eq!(check_branch_status("identical"), UpdateCheckResult::UpToDate);
+    }
+
+    #[test]
+    fn branch_available() {
+        let expected = UpdateCheckResult::Available { version: HEAD.to_string() };
+        assert_eq!(check_branch_status("ahead"), expected);
+    }
+
+    #[test]
+    fn branch_newer_installed() {
+        let expected = UpdateCheckResult::Ahead { version: HEAD.to_string() };
+        assert_eq!(check_branch_status("behind"), expected);
+    }
+
+    #[test]
+    fn branch_diverged() {
+        let expected = UpdateCheckResult::Available { version: HEAD.to_string() };
+        assert_eq!(check_branch_status("diverged"), expected);
+    }
+
+    #[test]
+    fn branch_error() {
+        // Сравнение недоступно: ошибка, а не предложение обновиться
+        let (_tree, source) = file_source(&[("branches/developer", &branch(HEAD))]);
+        assert!(matches!(check(&source, Channel::Developer, Some(INSTALLED)), UpdateCheckResult::Error(_)));
+
+        assert!(matches!(check_branch_status("unknown"), UpdateCheckResult::Error(_)));
+
+        let (_tree, source) = file_source(&[]);
+        assert!(matches!(check(&source, Channel::Experimental, Some(INSTALLED)), UpdateCheckResult::Error(_)));
+    }
+}
@@ -0,0 +1,147 @@
+use std::cmp::Ordering;
+use semver::Version;
+
+// Длина, начиная с которой сокращённый хеш коммита считается однозначным
+const MIN_HASH_PREFIX: usize = 7;
+
+// Тег релиза как semver: допускаются префикс v и сокращённые версии вида 1.2 или 1.2-rc1
+pub fn parse_release(tag: &str) -> Option<Version> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+    if let Ok(version) = Version::parse(tag) {
+        return Some(version);
+    }
+
+    let (core, suffix) = tag.split_at(tag.find(['-', '+']).unwrap_or(tag.len()));
+    let components = core.split('.').count();
+    if core.is_empty() || components >= 3 {
+        return None;
+    }
+
+    Version::parse(&format!("{}{}{}", core, ".0".repeat(3 - components), suffix)).ok()
+}
+
+// Порядок релизов по правилам semver без учёта метаданных сборки;
+// None, если хотя бы один тег не является версией
+pub fn compare_releases(left: &str, right: &str) -> Option<Ordering> {
+    Some(parse_release(left)?.cmp_precedence(&parse_release(right)?))
+}
+
+// Совпадение коммитов, в том числе по сокращённому хешу
+pub fn same_commit(left: &str, right: &str) -> bool {
+    let (left, right) = (left.trim(), right.trim());
+    let (short, long) = match left.len() <= right.len() {
+        true => (left, right),
+        false => (right, left),
+    };
+
+    short == long || (short.len() >= MIN_HASH_PREFIX && long.starts_with(short))
+}
+
+// Чем будет переход на выбранную версию для установленной системы
+#[derive(Clone, Copy, PartialEq)]
+pub enum VersionChange {
+    Upgrade,
+    Downgrade,
+    Reinstall,
+}
+
+// versions - список от новых к старым. Теги сравниваются как semver,
+// коммиты - по положению в списке; неизвестная установленная версия считается старой
+pub fn version_change(versions: &[&str], target: usize, current: Option<&str>) -> VersionChange {
+    let Some(current) = current else {
+        return VersionChange::Upgrade;
+    };
+    if same_commit(versions[target], current) {
+        return VersionChange::Reinstall;
+    }
+
+    let by_position = || match versions.iter().position(|version| same_commit(version, current)) {
+        Some(installed) if installed < target => VersionChange::Downgrade,
+        _ => VersionChange::Upgrade,
+    };
+
+    match compare_releases(versions[target], current) {
+        Some(Ordering::Greater) => VersionChange::Upgrade,
+        Some(Ordering::Less) => VersionChange::Downgrade,
+        Some(Ordering::Equal) => VersionChange::Reinstall,
+        None => by_position(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_short_and_prefixed() {
+        assert_eq!(parse_release("v1.2"), Some(Version::new(1, 2, 0)));
+        assert_eq!(parse_release(" V2 "), Some(Version::new(2, 0, 0)));
+        assert_eq!(parse_release("1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(parse_release("v1.2-rc1"), Version::parse("1.2.0-rc1").ok());
+        assert_eq!(parse_release("1.2+build.5"), Version::parse("1.2.0+build.5").ok());
+    }
+
+    #[test]
+    fn parse_non_semver() {
+        for tag in ["", "v", "latest", "nightly-2026-10-01", "1.2.3.4", "v1.x", "5d1c2b3a4f5e6d7c"] {
+            assert_eq!(parse_release(tag), None, "{}", tag);
+        }
+    }
+
+    #[test]
+    fn compare_short_and_full() {
+        assert_eq!(compare_releases("v1.2", "1.2.0"), Some(Ordering::Equal));
+        assert_eq!(compare_releases("v1.10.0", "v1.9.0"), Some(Ordering::Greater));
+        assert_eq!(compare_releases("1.2", "v1.2.1"), Some(Ordering::Less));
+        // Метаданные сборки не влияют на порядок
+        assert_eq!(compare_releases("1.2.0+a", "1.2.0+b"), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn compare_prereleases() {
+        assert_eq!(compare_releases("v1.4.0-rc1", "v1.4.0"), Some(Ordering::Less));
+        assert_eq!(compare_releases("v1.4.0-rc1", "v1.3.0"), Some(Ordering::Greater));
+        assert_eq!(compare_releases("1.4.0-alpha", "1.4.0-beta"), Some(Ordering::Less));
+        assert_eq!(compare_releases("1.4.0-rc.2", "1.4.0-rc.10"), Some(Ordering::Less));
+        assert_eq!(compare_releases("v1.4-rc1", "1.4.0-rc1"), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn compare_non_semver() {
+        assert_eq!(compare_releases("latest", "v1.2.0"), None);
+        assert_eq!(compare_releases("v1.2.0", "5d1c2b3"), None);
+    }
+
+    #[test]
+    fn same_commit_prefix() {
+        let sha = "5d1c2b3a4f5e6d7c8b9a0f1e2d3c4b5a6f7e8d9c";
+        assert!(same_commit(sha, sha));
+        assert!(same_commit(&sha[..7], sha));
+        assert!(same_commit(sha, &format!("{}\n", &sha[..12])));
+        // Слишком короткий префикс неоднозначен
+        assert!(!same_commit(&sha[..6], sha));
+        assert!(!same_commit("5d1c2b3a", "5d1c2b3b"));
+        assert!(same_commit("v1.2.0", "v1.2.0"));
+        assert!(!same_commit("v1.2", "v1.2.0"));
+    }
+
+    #[test]
+    fn version_change_by_release() {
+        let versions = ["v1.4.0", "v1.3.0", "v1.2.0"];
+        assert!(version_change(&versions, 0, Some("1.3")) == VersionChange::Upgrade);
+        assert!(version_change(&versions, 2, Some("v1.3.0")) == VersionChange::Downgrade);
+        assert!(version_change(&versions, 1, Some("v1.3")) == VersionChange::Reinstall);
+        assert!(version_change(&versions, 2, None) == VersionChange::Upgrade);
+    }
+
+    #[test]
+    fn version_change_by_position() {
+        let versions = ["cccccccccc", "bbbbbbbbbb", "aaaaaaaaaa"];
+        assert!(version_change(&versions, 0, Some("bbbbbbb")) == VersionChange::Upgrade);
+        assert!(version_change(&versions, 2, Some("bbbbbbb")) == VersionChange::Downgrade);
+        assert!(version_change(&versions, 1, Some("bbbbbbb")) == VersionChange::Reinstall);
+        // Установленный коммит вне списка считается старым
+        assert!(version_change(&versions, 2, Some("ddddddd")) == VersionChange::Upgrade);
+    }
+}
@@ -0,0 +1,116 @@
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use serde::Serialize;
+use crate::check_cache::{CheckReport, CheckStatus};
+use crate::localization::{Language, L10N};
+use crate::summary::{check_status_text, short_version, SystemSummary};
+
+// Терминал для on-click, если не задан $TERMINAL: Kite ставит kitty
+const DEFAULT_TERMINAL: &str = "kitty";
+// Аргумент перед запускаемой командой; kitty и foot принимают команду без него, остальные - после -e
+const TERMINAL_EXEC_ARGS: [(&str, Option<&str>); 5] = [
+    ("kitty", None),
+    ("foot", None),
+    ("wezterm", Some("start")),
+    ("gnome-terminal", Some("--")),
+    ("xfce4-terminal", Some("-x")),
+];
+const DEFAULT_EXEC_ARG: &str = "-e";
+
+// Вывод для custom-модуля waybar с "return-type": "json"
+#[derive(Serialize)]
+pub struct WaybarModule {
+    pub text: String,
+    pub tooltip: String,
+    pub class: &'static str,
+    // 100 - система актуальна, 0 - есть обновление, 50 - неизвестно; для format-icons
+    pub percentage: u8,
+}
+
+impl WaybarModule {
+    // Только данные с диска: модуль опрашивается часто и не должен ходить в сеть
+    pub fn new(summary: &SystemSummary, report: Option<&CheckReport>, language: Language) -> Self {
+        let field = |key: &str, value: &str| format!("{}: {}", L10N.get(key, language), value);
+        if !summary.installed {
+            let state = match summary.detected {
+                true => L10N.get("summary_not_installed", language),
+                false => L10N.get("summary_not_detected", language),
+            };
+            return Self {
+                text: "-".to_string(),
+                tooltip: field("summary_state", &state),
+                class: "not-installed",
+                percentage: 50,
+            };
+        }
+
+        // Канал перед версией: "stable 1.2.0", "developer 5d1c2b3 → 0a1b2c3"
+        let version = summary.version.as_deref().map_or("-", short_version);
+        let version = match summary.channel.as_deref() {
+            Some(channel) => format!("{} {}", channel, version),
+            None => version.to_string(),
+        };
+        let status = report.map(|report| report.status);
+        let text = match (status, report.and_then(|report| report.latest_version.as_deref())) {
+            (Some(CheckStatus::Available), Some(latest)) => format!("{} → {}", version, short_version(latest)),
+            _ => version,
+        };
+
+        let (class, percentage) = match status {
+            Some(CheckStatus::UpToDate) => ("up-to-date", 100),
+            Some(CheckStatus::Ahead) => ("ahead", 100),
+            Some(CheckStatus::Available) => ("update-available", 0),
+            Some(CheckStatus::Error) => ("error", 50),
+            None => ("unknown", 50),
+        };
+
+        let last_check = match report {
+            Some(report) => format!(
+                "{} ({})",
+                check_status_text(&report.record().result, language),
+                report.checked_at.format("%Y-%m-%d %H:%M"),
+            ),
+            None => L10N.get("summary_check_never", language),
+        };
+        let tooltip = [
+            field("summary_system", &summary.name),
+            field("summary_channel", summary.channel.as_deref().unwrap_or("-")),
+            field("summary_version", summary.version.as_deref().unwrap_or("-")),
+            field("summary_last_check", &last_check),
+        ]
+        .join("\n");
+
+        Self {
+            text,
+            tooltip,
+            class,
+            percentage,
+        }
+    }
+}
+
+// waybar запускает on-click без терминала, поэтому интерфейс открывается в новом окне.
+// $TERMINAL может содержать аргументы: "kitty --single-instance"
+pub fn open_in_terminal() -> io::Result<()> {
+    let terminal = env::var("TERMINAL")
+        .ok()
+        .filter(|terminal| !terminal.trim().is_empty())
+        .unwrap_or(DEFAULT_TERMINAL.to_string());
+    let mut words = terminal.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_TERMINAL);
+
+    let name = Path::new(program).file_name().and_then(|name| name.to_str()).unwrap_or(program);
+    let exec_arg = TERMINAL_EXEC_ARGS.iter()
+        .find(|(terminal, _)| *terminal == name)
+        .map_or(Some(DEFAULT_EXEC_ARG), |(_, exec_arg)| *exec_arg);
+
+    Command::new(program)
+        .args(words)
+        .args(exec_arg)
+        .arg(env::current_exe()?)
+        .args(["waybar", "--on-click"])
+        .spawn()
+        .map(|_| ())
+}